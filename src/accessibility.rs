@@ -0,0 +1,17 @@
+//! Accessibility options that affect how the game presents itself.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Accessibility>();
+    app.init_resource::<Accessibility>();
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Turn off screen shake, hit-stop and other effects that move or freeze the view.
+    pub reduced_motion: bool,
+}
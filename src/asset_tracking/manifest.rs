@@ -0,0 +1,287 @@
+//! Asset manifests: data files that say which assets fill a resource collection.
+//!
+//! A manifest is a `.assets.ron` file mapping each field of a collection to an
+//! asset path, or a list of paths for `Vec<Handle<_>>` fields:
+//!
+//! ```ron
+//! {
+//!     "music": ["audio/music/Fluffing A Duck.ogg"],
+//!     "icon": "images/ducky.png",
+//! }
+//! ```
+//!
+//! Fields are filled through reflection, so collections only need to derive
+//! [`Reflect`] and [`Default`]. The asset type of each handle must be
+//! registered with [`RegisterManifestAsset::register_manifest_asset`], and
+//! every handle field must be listed in the manifest.
+//!
+//! Run `cargo run --bin check_manifests` to check that every path exists.
+
+use std::{any::TypeId, marker::PhantomData};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, ReflectHandle, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{ReflectMut, ReflectRef, TypeRegistry, TypeRegistryArc},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// A resource of asset handles that is filled in from a manifest.
+pub trait AssetCollection: Resource + Asset + Clone + Default + Reflect {}
+
+impl<T: Resource + Asset + Clone + Default + Reflect> AssetCollection for T {}
+
+pub trait RegisterManifestAsset {
+    /// Allow manifests to load assets of type `A`.
+    fn register_manifest_asset<A: Asset>(&mut self) -> &mut Self;
+}
+
+impl RegisterManifestAsset for App {
+    fn register_manifest_asset<A: Asset>(&mut self) -> &mut Self {
+        self.register_type::<Handle<A>>();
+        self.register_type_data::<Handle<A>, ReflectHandle>();
+        self
+    }
+}
+
+/// One entry of a manifest.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ManifestEntry {
+    Path(String),
+    Paths(Vec<String>),
+}
+
+/// Loads an [`AssetCollection`] from its manifest.
+pub(super) struct ManifestLoader<T> {
+    type_registry: TypeRegistryArc,
+    _collection: PhantomData<fn() -> T>,
+}
+
+impl<T> ManifestLoader<T> {
+    pub(super) fn new(type_registry: TypeRegistryArc) -> Self {
+        Self {
+            type_registry,
+            _collection: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestLoaderError {
+    #[error("Could not read manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("{0} is not a struct, so it can't be loaded from a manifest")]
+    NotAStruct(&'static str),
+    #[error("{collection} has no field named `{field}`")]
+    UnknownField {
+        collection: &'static str,
+        field: String,
+    },
+    #[error("{collection} needs a path for `{field}`, but the manifest doesn't list one")]
+    MissingField {
+        collection: &'static str,
+        field: String,
+    },
+    #[error("`{0}` needs a single path for a handle, or a list of paths for a list of handles")]
+    WrongShape(String),
+    #[error("`{0}` isn't a handle to an asset type registered with `register_manifest_asset`")]
+    NotAHandle(String),
+}
+
+impl<T: AssetCollection> AssetLoader for ManifestLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = ManifestLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest: HashMap<String, ManifestEntry> = ron::de::from_bytes(&bytes)?;
+
+        let type_registry = self.type_registry.read();
+        fill_collection(&type_registry, manifest, |asset_type, path| {
+            load_context
+                .loader()
+                .with_dynamic_type(asset_type)
+                .load(path)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["assets.ron"]
+    }
+}
+
+/// Fill in a `T` from a parsed manifest, using `load` to start loading each
+/// path as the given asset type.
+fn fill_collection<T: AssetCollection>(
+    type_registry: &TypeRegistry,
+    manifest: HashMap<String, ManifestEntry>,
+    mut load: impl FnMut(TypeId, &str) -> UntypedHandle,
+) -> Result<T, ManifestLoaderError> {
+    let mut collection = T::default();
+    let ReflectMut::Struct(fields) = collection.reflect_mut() else {
+        return Err(ManifestLoaderError::NotAStruct(T::type_path()));
+    };
+    // Handle fields left out of the manifest would silently stay default.
+    let mut missing = (0..fields.field_len())
+        .filter(|&index| {
+            fields
+                .field_at(index)
+                .is_some_and(|field| is_handle_field(type_registry, field))
+        })
+        .filter_map(|index| fields.name_at(index).map(str::to_owned))
+        .collect::<Vec<_>>();
+    for (name, entry) in manifest {
+        missing.retain(|field| *field != name);
+        let Some(field) = fields.field_mut(&name) else {
+            return Err(ManifestLoaderError::UnknownField {
+                collection: T::short_type_path(),
+                field: name,
+            });
+        };
+        let field_type = field.get_represented_type_info().map(|info| info.type_id());
+        match (field.reflect_mut(), entry) {
+            (ReflectMut::List(list), ManifestEntry::Paths(paths)) => {
+                let Some(item_type) = list
+                    .get_represented_list_info()
+                    .map(|info| info.item_ty().id())
+                else {
+                    return Err(ManifestLoaderError::NotAHandle(name));
+                };
+                for path in paths {
+                    let handle = load_handle(type_registry, item_type, &path, &mut load, &name)?;
+                    list.push(handle.into_partial_reflect());
+                }
+            }
+            (ReflectMut::Enum(_), ManifestEntry::Path(path)) => {
+                let handle = field_type
+                    .ok_or_else(|| ManifestLoaderError::NotAHandle(name.clone()))
+                    .and_then(|field_type| {
+                        load_handle(type_registry, field_type, &path, &mut load, &name)
+                    })?;
+                field
+                    .try_as_reflect_mut()
+                    .and_then(|field| field.set(handle).ok())
+                    .ok_or(ManifestLoaderError::NotAHandle(name))?;
+            }
+            _ => return Err(ManifestLoaderError::WrongShape(name)),
+        }
+    }
+    if let Some(field) = missing.into_iter().next() {
+        return Err(ManifestLoaderError::MissingField {
+            collection: T::short_type_path(),
+            field,
+        });
+    }
+    Ok(collection)
+}
+
+/// Whether `field` is a handle, or a list of handles, that a manifest can fill in.
+fn is_handle_field(type_registry: &TypeRegistry, field: &dyn PartialReflect) -> bool {
+    let handle_type = match field.reflect_ref() {
+        ReflectRef::List(list) => list
+            .get_represented_list_info()
+            .map(|info| info.item_ty().id()),
+        _ => field.get_represented_type_info().map(|info| info.type_id()),
+    };
+    handle_type.is_some_and(|handle_type| {
+        type_registry
+            .get_type_data::<ReflectHandle>(handle_type)
+            .is_some()
+    })
+}
+
+/// Start loading `path` as a dependency, returning a reflected `Handle<_>` of the given type.
+fn load_handle(
+    type_registry: &TypeRegistry,
+    handle_type: TypeId,
+    path: &str,
+    load: &mut impl FnMut(TypeId, &str) -> UntypedHandle,
+    field: &str,
+) -> Result<Box<dyn Reflect>, ManifestLoaderError> {
+    let reflect_handle = type_registry
+        .get_type_data::<ReflectHandle>(handle_type)
+        .ok_or_else(|| ManifestLoaderError::NotAHandle(field.to_string()))?;
+    let handle = load(reflect_handle.asset_type_id(), path);
+    Ok(reflect_handle.typed(handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Asset, Clone, Reflect, Default)]
+    struct TestAssets {
+        icon: Handle<Image>,
+        frames: Vec<Handle<Image>>,
+        speed: f32,
+    }
+
+    /// Fill in [`TestAssets`] from `manifest`, returning the paths it loaded.
+    fn fill(manifest: &str) -> Result<(TestAssets, Vec<String>), ManifestLoaderError> {
+        let mut type_registry = TypeRegistry::default();
+        type_registry.register::<Handle<Image>>();
+        type_registry.register_type_data::<Handle<Image>, ReflectHandle>();
+        let manifest = ron::from_str(manifest).unwrap();
+        let mut paths = Vec::new();
+        let assets = fill_collection(&type_registry, manifest, |asset_type, path| {
+            assert_eq!(asset_type, TypeId::of::<Image>());
+            paths.push(path.to_string());
+            Handle::<Image>::default().untyped()
+        })?;
+        paths.sort();
+        Ok((assets, paths))
+    }
+
+    #[test]
+    fn fills_handles_and_lists() {
+        let (assets, paths) =
+            fill(r#"{ "icon": "icon.png", "frames": ["0.png", "1.png"] }"#).unwrap();
+        assert_eq!(paths, ["0.png", "1.png", "icon.png"]);
+        assert_eq!(assets.frames.len(), 2);
+        assert_eq!(assets.speed, 0.0);
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let error = fill(r#"{ "icon": "icon.png", "frames": [], "sound": "a.ogg" }"#);
+        assert!(matches!(
+            error,
+            Err(ManifestLoaderError::UnknownField { field, .. }) if field == "sound"
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_handle_fields() {
+        let error = fill(r#"{ "icon": "icon.png" }"#);
+        assert!(matches!(
+            error,
+            Err(ManifestLoaderError::MissingField { field, .. }) if field == "frames"
+        ));
+    }
+
+    #[test]
+    fn rejects_wrongly_shaped_fields() {
+        let error = fill(r#"{ "icon": ["icon.png"], "frames": [] }"#);
+        assert!(matches!(error, Err(ManifestLoaderError::WrongShape(field)) if field == "icon"));
+        let error = fill(r#"{ "icon": "icon.png", "frames": "0.png" }"#);
+        assert!(matches!(error, Err(ManifestLoaderError::WrongShape(field)) if field == "frames"));
+    }
+
+    #[test]
+    fn rejects_paths_for_fields_that_arent_handles() {
+        let error = fill(r#"{ "icon": "icon.png", "frames": [], "speed": "fast.png" }"#);
+        assert!(matches!(error, Err(ManifestLoaderError::WrongShape(field)) if field == "speed"));
+    }
+}
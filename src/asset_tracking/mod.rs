@@ -0,0 +1,272 @@
+//! A high-level way to load collections of asset handles as resources.
+//!
+//! Each collection lists its assets in a manifest file (see [`manifest`]).
+//! Resources needed everywhere are loaded once at startup with
+//! [`LoadResource::load_resource`]. Resources only needed in some states (a
+//! screen, a menu, a level) use [`LoadResource::load_resource_in_state`]
+//! instead, so their assets are dropped again when the state is left.
+//! Resources that change during play (like the current level's assets) are
+//! registered with [`LoadResource::init_resource_collection`], then loaded and
+//! dropped by hand with [`start_loading_resource`] and [`unload_resource`].
+
+mod manifest;
+
+use std::{any::TypeId, collections::VecDeque};
+
+use bevy::{
+    asset::{AssetPath, LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    prelude::*,
+};
+
+pub use manifest::{AssetCollection, RegisterManifestAsset};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ResourceHandles>();
+    app.add_systems(PreUpdate, load_resource_assets);
+}
+
+pub trait LoadResource {
+    /// Allow `T` to be loaded from a manifest with [`start_loading_resource`].
+    fn init_resource_collection<T: AssetCollection>(&mut self) -> &mut Self;
+
+    /// This will load the [`Resource`] as an [`Asset`] from the manifest at `path`. When all of
+    /// its asset dependencies have been loaded, it will be inserted as a resource. This ensures
+    /// that the resource only exists when the assets are ready.
+    fn load_resource<T: AssetCollection>(&mut self, path: &'static str) -> &mut Self;
+
+    /// Like [`LoadResource::load_resource`], but only starts loading when `state` is entered.
+    /// When it is exited, the resource is removed and its assets are dropped.
+    fn load_resource_in_state<T: AssetCollection, S: States>(
+        &mut self,
+        path: &'static str,
+        state: S,
+    ) -> &mut Self;
+}
+
+impl LoadResource for App {
+    fn init_resource_collection<T: AssetCollection>(&mut self) -> &mut Self {
+        self.init_asset::<T>();
+        let type_registry = self.world().resource::<AppTypeRegistry>().0.clone();
+        self.register_asset_loader(manifest::ManifestLoader::<T>::new(type_registry));
+        self
+    }
+
+    fn load_resource<T: AssetCollection>(&mut self, path: &'static str) -> &mut Self {
+        self.init_resource_collection::<T>();
+        start_loading_resource::<T>(self.world_mut(), path);
+        self
+    }
+
+    fn load_resource_in_state<T: AssetCollection, S: States>(
+        &mut self,
+        path: &'static str,
+        state: S,
+    ) -> &mut Self {
+        self.init_resource_collection::<T>();
+        self.add_systems(OnEnter(state.clone()), move |world: &mut World| {
+            start_loading_resource::<T>(world, path);
+        });
+        self.add_systems(OnExit(state), unload_resource::<T>);
+        self
+    }
+}
+
+/// Start loading `T` from the manifest at `path`. It will be inserted as a
+/// resource once its assets have loaded.
+pub fn start_loading_resource<T: AssetCollection>(
+    world: &mut World,
+    path: impl Into<AssetPath<'static>>,
+) {
+    let path = path.into();
+    let handle = world
+        .resource::<AssetServer>()
+        .load::<T>(path.clone())
+        .untyped();
+    let mut handles = world.resource_mut::<ResourceHandles>();
+    handles.waiting.push_back(WaitingResource {
+        handle,
+        path,
+        insert: |world, handle| {
+            let assets = world.resource::<Assets<T>>();
+            if let Some(value) = assets.get(handle.id().typed::<T>()) {
+                world.insert_resource(value.clone());
+            }
+        },
+        dependencies: |world, handle| {
+            let value = world
+                .resource::<Assets<T>>()
+                .get(handle.id().typed::<T>())?;
+            let mut dependencies = Vec::new();
+            value.visit_dependencies(&mut |id| dependencies.push(id));
+            Some(dependencies)
+        },
+    });
+}
+
+/// Remove the resource and drop its handles, whether it finished loading or not.
+pub fn unload_resource<T: AssetCollection>(world: &mut World) {
+    world.remove_resource::<T>();
+    let mut handles = world.resource_mut::<ResourceHandles>();
+    let is_other = |handle: &UntypedHandle| handle.type_id() != TypeId::of::<T>();
+    handles.waiting.retain(|waiting| is_other(&waiting.handle));
+    handles
+        .finished
+        .retain(|finished| is_other(&finished.handle));
+}
+
+/// A resource that is waiting for its assets to load.
+struct WaitingResource {
+    handle: UntypedHandle,
+    /// The manifest the resource is loaded from.
+    path: AssetPath<'static>,
+    /// Inserts the loaded resource.
+    insert: fn(&mut World, &UntypedHandle),
+    /// The assets the resource depends on, once its manifest has loaded.
+    dependencies: fn(&World, &UntypedHandle) -> Option<Vec<UntypedAssetId>>,
+}
+
+#[derive(Resource, Default)]
+pub struct ResourceHandles {
+    // Use a queue for waiting assets so they can be cycled through and moved to
+    // `finished` one at a time.
+    waiting: VecDeque<WaitingResource>,
+    finished: Vec<FinishedResource>,
+    total: usize,
+    loaded: usize,
+    failures: Vec<LoadFailure>,
+}
+
+/// A resource that has been inserted.
+struct FinishedResource {
+    handle: UntypedHandle,
+    /// How many assets the resource depends on.
+    dependencies: usize,
+}
+
+/// An asset that failed to load.
+#[derive(Debug, Clone)]
+pub struct LoadFailure {
+    id: UntypedAssetId,
+    /// The asset's path, relative to the `assets` folder.
+    pub path: String,
+    pub error: String,
+}
+
+impl ResourceHandles {
+    /// Returns true if all requested [`Asset`]s have finished loading and are available as [`Resource`]s.
+    pub fn is_all_done(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    /// How many assets the requested resources depend on. Until a resource's
+    /// manifest has loaded, the manifest counts as its only asset.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// How many of those assets have loaded, including their own dependencies.
+    pub fn loaded(&self) -> usize {
+        self.loaded
+    }
+
+    /// The assets that failed to load. Loading can't finish until they're retried.
+    pub fn failures(&self) -> &[LoadFailure] {
+        &self.failures
+    }
+
+    /// How much has been loaded, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
+}
+
+fn load_resource_assets(world: &mut World) {
+    world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
+        world.resource_scope(|world, assets: Mut<AssetServer>| {
+            let finished_dependencies: usize = resource_handles
+                .finished
+                .iter()
+                .map(|finished| finished.dependencies)
+                .sum();
+            let mut loaded = finished_dependencies;
+            let mut total = finished_dependencies;
+            let mut failures = Vec::new();
+            for _ in 0..resource_handles.waiting.len() {
+                let waiting = resource_handles.waiting.pop_front().unwrap();
+                let dependencies = (waiting.dependencies)(world, &waiting.handle)
+                    .unwrap_or_else(|| vec![waiting.handle.id()]);
+                total += dependencies.len();
+                if assets.is_loaded_with_dependencies(&waiting.handle) {
+                    (waiting.insert)(world, &waiting.handle);
+                    loaded += dependencies.len();
+                    resource_handles.finished.push(FinishedResource {
+                        handle: waiting.handle,
+                        dependencies: dependencies.len(),
+                    });
+                    continue;
+                }
+
+                for id in dependencies {
+                    match load_failure(&assets, id) {
+                        Some(error) => failures.push(LoadFailure {
+                            id,
+                            path: assets
+                                .get_path(id)
+                                .map_or_else(|| format!("{id:?}"), |path| path.to_string()),
+                            error,
+                        }),
+                        None if assets.is_loaded_with_dependencies(id) => loaded += 1,
+                        None => {}
+                    }
+                }
+                resource_handles.waiting.push_back(waiting);
+            }
+
+            for failure in &failures {
+                if !resource_handles
+                    .failures
+                    .iter()
+                    .any(|known| known.id == failure.id)
+                {
+                    error!("Failed to load {}: {}", failure.path, failure.error);
+                }
+            }
+            resource_handles.loaded = loaded;
+            resource_handles.total = total;
+            resource_handles.failures = failures;
+        });
+    });
+}
+
+/// Why an asset or one of its dependencies failed to load, if it did.
+fn load_failure(assets: &AssetServer, id: UntypedAssetId) -> Option<String> {
+    if let Some(LoadState::Failed(error)) = assets.get_load_state(id) {
+        return Some(error.to_string());
+    }
+    match assets.get_recursive_dependency_load_state(id) {
+        Some(RecursiveDependencyLoadState::Failed(error)) => Some(error.to_string()),
+        _ => None,
+    }
+}
+
+/// Try loading every asset that failed again.
+pub fn retry_failed_loads(world: &mut World) {
+    world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
+        let assets = world.resource::<AssetServer>();
+        // Reload the failed assets themselves, since an asset that loaded but
+        // has a failed dependency won't be loaded again on request.
+        for failure in resource_handles.failures.drain(..) {
+            if let Some(path) = assets.get_path(failure.id) {
+                assets.reload(path.into_owned());
+            }
+        }
+        // Reload the manifests too, so they start waiting on their assets afresh.
+        for waiting in &resource_handles.waiting {
+            assets.reload(waiting.path.clone());
+        }
+    });
+}
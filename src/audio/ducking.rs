@@ -0,0 +1,55 @@
+//! Ducking: turning the music down while something more important is going on.
+//!
+//! Add [`DuckMusic`] to any entity to duck the music for as long as that entity
+//! exists. The pause overlay uses it, and so can a dialogue line or a stinger:
+//! put it on the voice or stinger's audio entity and the music comes back up
+//! once the sound despawns.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DuckMusic>();
+    app.register_type::<MusicDucking>();
+    app.init_resource::<MusicDucking>();
+    app.add_systems(Update, update_music_ducking);
+}
+
+/// Turns music down to `level` (a linear volume multiplier) while this entity exists.
+/// If several entities duck the music, the quietest level wins.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct DuckMusic(pub f32);
+
+/// How long it takes for music to reach its ducked level, or to come back up.
+const DUCK_FADE: Duration = Duration::from_millis(300);
+
+/// The current ducking multiplier for music, eased towards the quietest [`DuckMusic`].
+#[derive(Resource, Reflect, Debug, PartialEq)]
+#[reflect(Resource)]
+pub(super) struct MusicDucking {
+    pub(super) gain: f32,
+}
+
+impl Default for MusicDucking {
+    fn default() -> Self {
+        Self { gain: 1.0 }
+    }
+}
+
+fn update_music_ducking(
+    // Ducking mostly happens while the game is paused, so use real time.
+    time: Res<Time<Real>>,
+    duck_query: Query<&DuckMusic>,
+    mut ducking: ResMut<MusicDucking>,
+) {
+    let target = duck_query
+        .iter()
+        .map(|duck| duck.0)
+        .fold(1.0, f32::min)
+        .clamp(0.0, 1.0);
+    let max_step = time.delta_secs() / DUCK_FADE.as_secs_f32();
+    let gain = ducking.gain + (target - ducking.gain).clamp(-max_step, max_step);
+    ducking.set_if_neq(MusicDucking { gain });
+}
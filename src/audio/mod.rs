@@ -0,0 +1,258 @@
+mod ducking;
+mod music;
+mod positional;
+mod sound_bank;
+
+use std::time::Duration;
+
+use bevy::{
+    audio::{AudioSinkPlayback, Volume},
+    ecs::component::Mutable,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Pause, asset_tracking::RegisterManifestAsset};
+use ducking::MusicDucking;
+use positional::Attenuation;
+
+pub use ducking::DuckMusic;
+pub use music::{MusicDirector, Playlist};
+pub use positional::{AudioListener, Positional, SoundPosition};
+// Nothing plays a positional clip without a bank yet.
+#[allow(unused_imports)]
+pub use positional::sound_effect_at;
+pub use sound_bank::{SoundBank, sound_effect_from_bank, ui_sound_from_bank};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        ducking::plugin,
+        music::plugin,
+        positional::plugin,
+        sound_bank::plugin,
+    ));
+
+    app.register_manifest_asset::<AudioSource>();
+
+    app.register_type::<Music>();
+    app.register_type::<SoundEffect>();
+    app.register_type::<UiSound>();
+
+    app.register_type::<ChannelVolumes>();
+    app.init_resource::<ChannelVolumes>();
+    app.register_type::<Fade>();
+    app.add_systems(Update, tick_fades);
+    // Sinks are created in `PostUpdate`, so this catches new ones on the frame they start playing.
+    app.add_systems(
+        Last,
+        (
+            apply_volumes::<AudioSink>,
+            apply_volumes::<SpatialAudioSink>,
+        ),
+    );
+
+    // Sound effects belong to the game world, so they stop while it's paused.
+    // Music and UI sounds keep playing.
+    app.add_systems(
+        OnEnter(Pause(true)),
+        (
+            pause_sound_effects::<AudioSink>,
+            pause_sound_effects::<SpatialAudioSink>,
+        ),
+    );
+    app.add_systems(
+        OnExit(Pause(true)),
+        (
+            resume_sound_effects::<AudioSink>,
+            resume_sound_effects::<SpatialAudioSink>,
+        ),
+    );
+    app.add_systems(
+        Last,
+        (
+            pause_new_sound_effects::<AudioSink>,
+            pause_new_sound_effects::<SpatialAudioSink>,
+        )
+            .run_if(in_state(Pause(true))),
+    );
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "music" category (e.g. global background music, soundtrack).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Music;
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "sound effect" category (e.g. footsteps, the sound of a magic spell, a door opening).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SoundEffect;
+
+/// A sound effect audio instance. Use a [`SoundBank`] for anything that can play rapidly.
+pub fn sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::DESPAWN, SoundEffect)
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "UI" category (e.g. button hover and click sounds).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct UiSound;
+
+/// A UI sound audio instance. Use a [`SoundBank`] for anything that can play rapidly.
+// Button sounds all come from banks; this is for one-off clips such as stingers.
+#[allow(dead_code)]
+pub fn ui_sound(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::DESPAWN, UiSound)
+}
+
+/// Linear volume of each category of sound, applied on top of [`GlobalVolume`].
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct ChannelVolumes {
+    pub music: f32,
+    pub sound_effects: f32,
+    pub ui: f32,
+}
+
+impl Default for ChannelVolumes {
+    fn default() -> Self {
+        Self {
+            music: 1.0,
+            sound_effects: 1.0,
+            ui: 1.0,
+        }
+    }
+}
+
+/// Fades an audio entity's volume in or out.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Fade {
+    timer: Timer,
+    from: f32,
+    to: f32,
+    /// Despawn the entity once the fade is done, e.g. when fading out.
+    despawn: bool,
+}
+
+impl Fade {
+    pub fn fade_in(duration: Duration) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            from: 0.0,
+            to: 1.0,
+            despawn: false,
+        }
+    }
+
+    /// Fade out from the current level, then despawn.
+    pub fn fade_out(&self, duration: Duration) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            from: self.gain(),
+            to: 0.0,
+            despawn: true,
+        }
+    }
+
+    /// Linear volume multiplier at this point in the fade.
+    pub fn gain(&self) -> f32 {
+        self.from.lerp(self.to, self.timer.fraction())
+    }
+}
+
+fn tick_fades(
+    // Fades are about how things sound, so they keep going while the game is paused.
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+    mut fade_query: Query<(Entity, &mut Fade)>,
+) {
+    for (entity, mut fade) in &mut fade_query {
+        if fade.timer.finished() {
+            continue;
+        }
+        fade.timer.tick(time.delta());
+        if fade.timer.finished() && fade.despawn {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Bevy only applies [`GlobalVolume`] when a sink is created, and doesn't know
+/// about [`ChannelVolumes`], [`Fade`]s, [`DuckMusic`] or [`Positional`] sounds
+/// at all, so this system sets the volume of new sinks and updates sinks
+/// whenever any of those change.
+fn apply_volumes<S: Component<Mutability = Mutable> + AudioSinkPlayback>(
+    global_volume: Res<GlobalVolume>,
+    channel_volumes: Res<ChannelVolumes>,
+    ducking: Res<MusicDucking>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        &mut S,
+        Option<Ref<Fade>>,
+        Option<Ref<Attenuation>>,
+        Has<Music>,
+        Has<SoundEffect>,
+        Has<UiSound>,
+    )>,
+) {
+    let volumes_changed =
+        global_volume.is_changed() || channel_volumes.is_changed() || ducking.is_changed();
+    for (playback, mut sink, fade, attenuation, is_music, is_sound_effect, is_ui_sound) in
+        &mut audio_query
+    {
+        let fade_changed = fade.as_ref().is_some_and(|fade| fade.is_changed());
+        let attenuation_changed = attenuation
+            .as_ref()
+            .is_some_and(|attenuation| attenuation.is_changed());
+        if !volumes_changed && !fade_changed && !attenuation_changed && !sink.is_added() {
+            continue;
+        }
+        let fade = fade.map_or(1.0, |fade| fade.gain());
+        let attenuation = attenuation.map_or(1.0, |attenuation| attenuation.0);
+        let channel = if is_music {
+            channel_volumes.music * ducking.gain
+        } else if is_sound_effect {
+            channel_volumes.sound_effects
+        } else if is_ui_sound {
+            channel_volumes.ui
+        } else {
+            1.0
+        };
+        sink.set_volume(
+            global_volume.volume * Volume::Linear(channel * fade * attenuation) * playback.volume,
+        );
+    }
+}
+
+fn pause_sound_effects<S: Component + AudioSinkPlayback>(sink_query: Query<&S, With<SoundEffect>>) {
+    for sink in &sink_query {
+        sink.pause();
+    }
+}
+
+fn resume_sound_effects<S: Component + AudioSinkPlayback>(
+    sink_query: Query<&S, With<SoundEffect>>,
+) {
+    for sink in &sink_query {
+        sink.play();
+    }
+}
+
+/// Sound effects requested on the frame the game paused only get their sinks afterwards.
+fn pause_new_sound_effects<S: Component + AudioSinkPlayback>(
+    sink_query: Query<&S, (With<SoundEffect>, Added<S>)>,
+) {
+    for sink in &sink_query {
+        sink.pause();
+    }
+}
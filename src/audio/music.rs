@@ -0,0 +1,266 @@
+//! The music director, which owns whatever music is playing.
+//!
+//! Instead of spawning [`Music`] entities themselves, screens ask the
+//! [`MusicDirector`] for a track or a [`Playlist`]. The director crossfades from
+//! the old music to the new, and ignores requests for what's already playing,
+//! so screens can request their music on enter without caring what came before.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::audio::{Fade, Music};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MusicDirector>();
+    app.add_systems(Update, direct_music);
+}
+
+/// A list of tracks to play one after another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playlist {
+    pub tracks: Vec<Handle<AudioSource>>,
+    /// Play the tracks in a random order, reshuffled every time the list runs out.
+    pub shuffle: bool,
+}
+
+/// Owns the current music and crossfades between tracks.
+#[derive(Resource, Debug)]
+pub struct MusicDirector {
+    /// How long it takes for new music to fade in and old music to fade out.
+    pub crossfade: Duration,
+    selection: Option<Selection>,
+    /// The track that should be playing right now, if it isn't already.
+    pending: Option<PendingTrack>,
+    /// The entity playing the current track.
+    playing: Option<Entity>,
+}
+
+impl Default for MusicDirector {
+    fn default() -> Self {
+        Self {
+            crossfade: Duration::from_secs_f32(1.5),
+            selection: None,
+            pending: None,
+            playing: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Selection {
+    Track(Handle<AudioSource>),
+    Playlist {
+        playlist: Playlist,
+        /// The order to play the playlist's tracks in, as indices into `tracks`.
+        order: Vec<usize>,
+        position: usize,
+    },
+}
+
+#[derive(Debug)]
+struct PendingTrack {
+    track: Handle<AudioSource>,
+    looping: bool,
+}
+
+impl MusicDirector {
+    /// Loop a single track. Does nothing if it's already playing.
+    pub fn play(&mut self, track: Handle<AudioSource>) {
+        if matches!(&self.selection, Some(Selection::Track(current)) if *current == track) {
+            return;
+        }
+        self.pending = Some(PendingTrack {
+            track: track.clone(),
+            looping: true,
+        });
+        self.selection = Some(Selection::Track(track));
+    }
+
+    /// Play through a playlist, starting over when it runs out. Does nothing if
+    /// it's already playing.
+    pub fn play_playlist(&mut self, playlist: Playlist) {
+        if matches!(&self.selection, Some(Selection::Playlist { playlist: current, .. }) if *current == playlist)
+        {
+            return;
+        }
+        let order = playlist_order(&playlist, None);
+        self.pending = order.first().map(|&index| PendingTrack {
+            track: playlist.tracks[index].clone(),
+            looping: false,
+        });
+        self.selection = Some(Selection::Playlist {
+            playlist,
+            order,
+            position: 0,
+        });
+    }
+
+    /// Fade out the current music.
+    pub fn stop(&mut self) {
+        self.selection = None;
+        self.pending = None;
+    }
+
+    /// Move on to the next track of the playlist, if one is playing.
+    fn advance(&mut self) {
+        let Some(Selection::Playlist {
+            playlist,
+            order,
+            position,
+        }) = &mut self.selection
+        else {
+            return;
+        };
+        *position += 1;
+        if *position >= order.len() {
+            *order = playlist_order(playlist, order.last().copied());
+            *position = 0;
+        }
+        self.pending = order.get(*position).map(|&index| PendingTrack {
+            track: playlist.tracks[index].clone(),
+            looping: false,
+        });
+    }
+}
+
+/// The order to play a playlist in. When shuffling, avoid starting with
+/// `previous` so the same track doesn't play twice in a row.
+fn playlist_order(playlist: &Playlist, previous: Option<usize>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..playlist.tracks.len()).collect();
+    if playlist.shuffle {
+        order.shuffle(&mut rand::rng());
+        if order.len() > 1 && order.first() == previous.as_ref() {
+            order.swap(0, 1);
+        }
+    }
+    order
+}
+
+fn direct_music(
+    mut commands: Commands,
+    mut director: ResMut<MusicDirector>,
+    fade_query: Query<(Entity, &Fade)>,
+    sink_query: Query<&AudioSink>,
+) {
+    // Playlist tracks play once; move on when one finishes.
+    let finished = director
+        .playing
+        .and_then(|playing| sink_query.get(playing).ok())
+        .is_some_and(AudioSink::empty);
+    if director.pending.is_none() && finished {
+        director.advance();
+    }
+
+    let stopped = director.selection.is_none() && director.playing.is_some();
+    if director.pending.is_none() && !stopped {
+        return;
+    }
+
+    let crossfade = director.crossfade;
+    if let Some(Ok((playing, fade))) = director
+        .playing
+        .take()
+        .map(|playing| fade_query.get(playing))
+    {
+        commands.entity(playing).insert(fade.fade_out(crossfade));
+    }
+    if let Some(PendingTrack { track, looping }) = director.pending.take() {
+        let playback = if looping {
+            PlaybackSettings::LOOP
+        } else {
+            PlaybackSettings::ONCE
+        };
+        let entity = commands
+            .spawn((
+                Name::new("Music"),
+                AudioPlayer(track),
+                playback,
+                Music,
+                Fade::fade_in(crossfade),
+            ))
+            .id();
+        director.playing = Some(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::uuid::Uuid;
+
+    use super::*;
+
+    fn playlist(len: u128, shuffle: bool) -> Playlist {
+        Playlist {
+            tracks: (0..len)
+                .map(|index| {
+                    Handle::Weak(AssetId::Uuid {
+                        uuid: Uuid::from_u128(index),
+                    })
+                })
+                .collect(),
+            shuffle,
+        }
+    }
+
+    /// Every track, in the order the director queues them.
+    fn next_tracks(director: &mut MusicDirector, count: usize) -> Vec<Handle<AudioSource>> {
+        (0..count)
+            .map(|_| {
+                let track = director.pending.take().unwrap().track;
+                director.advance();
+                track
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plays_in_order_without_shuffle() {
+        assert_eq!(playlist_order(&playlist(4, false), Some(0)), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn shuffle_plays_every_track_once() {
+        for _ in 0..100 {
+            let mut order = playlist_order(&playlist(5, true), None);
+            order.sort();
+            assert_eq!(order, [0, 1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn shuffle_never_repeats_the_previous_track() {
+        for previous in 0..3 {
+            for _ in 0..100 {
+                let order = playlist_order(&playlist(3, true), Some(previous));
+                assert_ne!(order[0], previous);
+            }
+        }
+    }
+
+    #[test]
+    fn reshuffles_when_the_playlist_runs_out() {
+        let playlist = playlist(3, true);
+        let mut director = MusicDirector::default();
+        director.play_playlist(playlist.clone());
+        let tracks = next_tracks(&mut director, 30);
+        for round in tracks.chunks(3) {
+            for track in &playlist.tracks {
+                assert!(round.contains(track));
+            }
+        }
+        for pair in tracks.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn requesting_the_same_playlist_again_does_nothing() {
+        let mut director = MusicDirector::default();
+        director.play_playlist(playlist(3, true));
+        director.pending = None;
+        director.play_playlist(playlist(3, true));
+        assert!(director.pending.is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Positional audio for sounds that happen somewhere in the world.
+//!
+//! Add a [`SoundPosition`] to a sound effect to place it in the world. It gets
+//! quieter the further it is from the [`AudioListener`] (or the camera, if
+//! there is no listener), pans towards the side it comes from, and isn't played
+//! at all if it starts out of earshot. [`Positional`] tunes how distance
+//! affects it. [`sound_effect_at`] plays a single clip with both.
+//!
+//! Sound banks check whether a sound is in earshot before it takes up one of
+//! their voices, so a sound that is never heard can't cut off one that is.
+//!
+//! Bevy's spatial audio drops off with the square of the distance, which is far
+//! too steep for a world measured in pixels, so [`SpatialAudioSink`]s are only
+//! used for panning and the volume is handled here.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::audio::{SoundEffect, apply_volumes};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AudioListener>();
+    app.register_type::<SoundPosition>();
+    app.register_type::<Positional>();
+    app.register_type::<Attenuation>();
+    app.register_type::<LastPosition>();
+
+    app.add_observer(cull_distant_sounds);
+    // After the sinks of new sounds have been created in `PostUpdate`.
+    app.add_systems(
+        Last,
+        update_positional_sounds.before(apply_volumes::<AudioSink>),
+    );
+}
+
+/// A positional sound effect audio instance, heard according to `positional`.
+/// Use a [`SoundBank`](super::SoundBank) for anything that can play rapidly.
+#[allow(dead_code)]
+pub fn sound_effect_at(
+    handle: Handle<AudioSource>,
+    position: SoundPosition,
+    positional: Positional,
+) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.with_spatial(true),
+        SoundEffect,
+        position,
+        positional,
+    )
+}
+
+/// Positional sounds are heard from this entity. Without one, they are heard
+/// from the camera.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct AudioListener;
+
+/// Where a sound is coming from.
+///
+/// Bevy warns about spatial sounds without a [`Transform`], so one is required,
+/// but it's left alone: moving it would make Bevy move the sink's emitter too.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+#[require(Positional, Attenuation, LastPosition, Transform)]
+pub enum SoundPosition {
+    /// A fixed point in the world.
+    At(Vec2),
+    /// Follows an entity around. If the entity despawns, the sound stays where
+    /// the entity last was.
+    On(Entity),
+}
+
+/// How distance from the listener affects a positional sound. Distances are in
+/// world units.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Positional {
+    /// Within this distance, the sound plays at full volume.
+    pub min_distance: f32,
+    /// At this distance, the sound is silent. Sounds starting further away aren't played at all.
+    pub max_distance: f32,
+    /// How quickly the volume falls off between the two. 1 is linear, higher falls off faster at first.
+    pub rolloff: f32,
+    /// Sounds this far to either side are panned fully to that side.
+    pub pan_distance: f32,
+}
+
+impl Default for Positional {
+    fn default() -> Self {
+        Self {
+            min_distance: 64.0,
+            max_distance: 900.0,
+            rolloff: 2.0,
+            pan_distance: 500.0,
+        }
+    }
+}
+
+impl Positional {
+    /// The linear volume of a sound this far from the listener.
+    fn gain(&self, distance: f32) -> f32 {
+        let range = (self.max_distance - self.min_distance).max(f32::EPSILON);
+        let t = ((distance - self.min_distance) / range).clamp(0.0, 1.0);
+        (1.0 - t).powf(self.rolloff)
+    }
+
+    /// How far left (-1) or right (1) a sound at this offset from the listener is.
+    fn pan(&self, offset: Vec2) -> f32 {
+        (offset.x / self.pan_distance.max(f32::EPSILON)).clamp(-1.0, 1.0)
+    }
+}
+
+/// The volume of a positional sound due to its distance from the listener.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub(super) struct Attenuation(pub(super) f32);
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Where a positional sound was last frame, so it stays put once its emitter despawns.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component)]
+struct LastPosition(Vec2);
+
+/// Half the distance between the listener's ears, in the sink's own space.
+/// Keeping the emitter within a unit of both ears stops the sink from
+/// attenuating the sound itself.
+const EAR_OFFSET: f32 = 0.5;
+
+fn listener_position(
+    listener_query: &Query<&GlobalTransform, With<AudioListener>>,
+    camera_query: &Query<&GlobalTransform, With<Camera2d>>,
+) -> Option<Vec2> {
+    listener_query
+        .iter()
+        .next()
+        .or_else(|| camera_query.iter().next())
+        .map(|transform| transform.translation().truncate())
+}
+
+/// Tells whether positional sounds start close enough to the listener to be heard.
+#[derive(SystemParam)]
+pub(super) struct Earshot<'w, 's> {
+    emitter_query: Query<'w, 's, &'static GlobalTransform>,
+    listener_query: Query<'w, 's, &'static GlobalTransform, With<AudioListener>>,
+    camera_query: Query<'w, 's, &'static GlobalTransform, With<Camera2d>>,
+}
+
+impl Earshot<'_, '_> {
+    /// Whether a sound starting at `position` is too far away to hear. Sounds
+    /// are kept if there's no listener or their emitter is gone.
+    pub(super) fn out_of_range(&self, position: SoundPosition, positional: &Positional) -> bool {
+        let position = match position {
+            SoundPosition::At(position) => Some(position),
+            SoundPosition::On(emitter) => self
+                .emitter_query
+                .get(emitter)
+                .ok()
+                .map(|transform| transform.translation().truncate()),
+        };
+        position
+            .zip(listener_position(&self.listener_query, &self.camera_query))
+            .is_some_and(|(position, listener)| {
+                position.distance(listener) > positional.max_distance
+            })
+    }
+}
+
+fn cull_distant_sounds(
+    trigger: Trigger<OnAdd, SoundPosition>,
+    mut commands: Commands,
+    sound_query: Query<(&SoundPosition, &Positional)>,
+    earshot: Earshot,
+) {
+    let entity = trigger.target();
+    let Ok((&position, positional)) = sound_query.get(entity) else {
+        return;
+    };
+    if earshot.out_of_range(position, positional) {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+fn update_positional_sounds(
+    mut sound_query: Query<(
+        &mut SoundPosition,
+        &Positional,
+        &mut Attenuation,
+        &mut LastPosition,
+        Option<&SpatialAudioSink>,
+    )>,
+    emitter_query: Query<&GlobalTransform>,
+    listener_query: Query<&GlobalTransform, With<AudioListener>>,
+    camera_query: Query<&GlobalTransform, With<Camera2d>>,
+) {
+    let listener = listener_position(&listener_query, &camera_query);
+    for (mut position, positional, mut attenuation, mut last_position, sink) in &mut sound_query {
+        let point = match *position {
+            SoundPosition::At(point) => point,
+            SoundPosition::On(emitter) => match emitter_query.get(emitter) {
+                Ok(emitter) => emitter.translation().truncate(),
+                Err(_) => {
+                    *position = SoundPosition::At(last_position.0);
+                    last_position.0
+                }
+            },
+        };
+        last_position.0 = point;
+
+        let Some(listener) = listener else {
+            continue;
+        };
+        let offset = point - listener;
+        attenuation.set_if_neq(Attenuation(positional.gain(offset.length())));
+        if let Some(sink) = sink {
+            sink.set_ears_position(Vec3::X * -EAR_OFFSET, Vec3::X * EAR_OFFSET);
+            sink.set_emitter_position(Vec3::X * positional.pan(offset) * EAR_OFFSET);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Sound banks: groups of interchangeable sounds with limits on how often they play.
+//!
+//! Banks are described in `.sound.ron` files (see `assets/audio/sound_effects/`).
+//! Each time a bank plays, it picks a random variant with a random pitch and
+//! volume. A bank can limit how many of its sounds play at once and how soon
+//! it may play again, so rapid triggers (hovering over a row of buttons, a
+//! fast weapon) don't stack dozens of copies of the same sound.
+//!
+//! Spawn [`sound_effect_from_bank`] or [`ui_sound_from_bank`] to play one. Add
+//! a [`SoundPosition`] to a bank's sound effect to make it positional.
+
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    audio::Volume,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use rand::{Rng, seq::IndexedRandom};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    asset_tracking::RegisterManifestAsset,
+    audio::{Positional, SoundEffect, SoundPosition, UiSound, positional::Earshot},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<SoundBank>();
+    app.register_asset_loader(SoundBankLoader);
+    app.register_manifest_asset::<SoundBank>();
+
+    app.register_type::<PlaySoundBank>();
+    app.init_resource::<SoundBankVoices>();
+    app.add_observer(play_sound_bank);
+}
+
+/// A sound effect played from a [`SoundBank`].
+pub fn sound_effect_from_bank(bank: Handle<SoundBank>) -> impl Bundle {
+    (PlaySoundBank(bank), SoundEffect)
+}
+
+/// A UI sound played from a [`SoundBank`].
+pub fn ui_sound_from_bank(bank: Handle<SoundBank>) -> impl Bundle {
+    (PlaySoundBank(bank), UiSound)
+}
+
+/// A group of interchangeable sounds, loaded from a `.sound.ron` file.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SoundBank {
+    pub variants: Vec<Handle<AudioSource>>,
+    /// Playback speed range, which also changes the pitch.
+    pub pitch: (f32, f32),
+    /// Linear volume range.
+    pub volume: (f32, f32),
+    /// How many sounds from this bank may play at once, if limited.
+    pub max_voices: Option<usize>,
+    /// What to do when a sound is requested while `max_voices` are playing.
+    pub when_full: WhenFull,
+    /// How long after playing the bank ignores further requests.
+    pub cooldown: Duration,
+}
+
+/// What a [`SoundBank`] does when all of its voices are in use.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WhenFull {
+    /// Stop the oldest sound to make room for the new one.
+    #[default]
+    StealOldest,
+    /// Don't play the new sound.
+    Skip,
+}
+
+/// Picks a sound from the bank, then turns into a regular [`AudioPlayer`]
+/// (or despawns if the bank is on cooldown or full).
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct PlaySoundBank(pub Handle<SoundBank>);
+
+/// The sounds each bank is playing, oldest first, and when it last played.
+#[derive(Resource, Debug, Default)]
+struct SoundBankVoices(HashMap<AssetId<SoundBank>, BankVoices>);
+
+#[derive(Debug, Default)]
+struct BankVoices {
+    playing: Vec<Entity>,
+    last_played: Option<Duration>,
+}
+
+fn play_sound_bank(
+    trigger: Trigger<OnAdd, PlaySoundBank>,
+    mut commands: Commands,
+    // Menus play banks while the game is paused, so use real time.
+    time: Res<Time<Real>>,
+    banks: Res<Assets<SoundBank>>,
+    mut voices: ResMut<SoundBankVoices>,
+    voice_query: Query<(&PlaySoundBank, Option<(&SoundPosition, &Positional)>)>,
+    earshot: Earshot,
+) {
+    let entity = trigger.target();
+    let Ok((play, position)) = voice_query.get(entity) else {
+        return;
+    };
+    // Positional sounds that start out of earshot are culled, so they mustn't
+    // take a voice or start the cooldown.
+    if position.is_some_and(|(&position, positional)| earshot.out_of_range(position, positional)) {
+        commands.entity(entity).try_despawn();
+        return;
+    }
+    let positional = position.is_some();
+    let bank_id = play.0.id();
+    let Some(bank) = banks.get(bank_id) else {
+        commands.entity(entity).try_despawn();
+        return;
+    };
+
+    let now = time.elapsed();
+    let bank_voices = voices.0.entry(bank_id).or_default();
+    // Voices despawn on their own once they finish playing.
+    bank_voices
+        .playing
+        .retain(|&voice| voice != entity && voice_query.contains(voice));
+
+    let on_cooldown = bank_voices
+        .last_played
+        .is_some_and(|last_played| now < last_played + bank.cooldown);
+    let full = bank
+        .max_voices
+        .is_some_and(|max_voices| bank_voices.playing.len() >= max_voices);
+    let rng = &mut rand::rng();
+    let variant = bank.variants.choose(rng);
+    let skip = on_cooldown || (full && bank.when_full == WhenFull::Skip);
+    let Some(variant) = variant.filter(|_| !skip) else {
+        commands.entity(entity).try_despawn();
+        return;
+    };
+    if full {
+        let oldest = bank_voices.playing.remove(0);
+        commands.entity(oldest).try_despawn();
+    }
+
+    bank_voices.playing.push(entity);
+    bank_voices.last_played = Some(now);
+    let speed = random_in(rng, bank.pitch);
+    let volume = random_in(rng, bank.volume);
+    commands.entity(entity).try_insert((
+        AudioPlayer(variant.clone()),
+        PlaybackSettings::DESPAWN
+            .with_speed(speed)
+            .with_volume(Volume::Linear(volume))
+            .with_spatial(positional),
+    ));
+}
+
+fn random_in(rng: &mut impl Rng, (min, max): (f32, f32)) -> f32 {
+    if min < max {
+        rng.random_range(min..=max)
+    } else {
+        min
+    }
+}
+
+/// The on-disk format of a [`SoundBank`].
+#[derive(Deserialize, Debug)]
+struct SoundBankFile {
+    /// Paths relative to the `assets` folder.
+    variants: Vec<String>,
+    #[serde(default = "default_range")]
+    pitch: (f32, f32),
+    #[serde(default = "default_range")]
+    volume: (f32, f32),
+    #[serde(default)]
+    max_voices: Option<usize>,
+    #[serde(default)]
+    when_full: WhenFull,
+    /// In seconds.
+    #[serde(default)]
+    cooldown: f32,
+}
+
+fn default_range() -> (f32, f32) {
+    (1.0, 1.0)
+}
+
+#[derive(Default)]
+struct SoundBankLoader;
+
+#[derive(Debug, Error)]
+pub enum SoundBankLoaderError {
+    #[error("Could not read sound bank: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse sound bank: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Sound bank has no variants")]
+    NoVariants,
+    #[error("Sound bank must allow at least one voice")]
+    NoVoices,
+}
+
+impl AssetLoader for SoundBankLoader {
+    type Asset = SoundBank;
+    type Settings = ();
+    type Error = SoundBankLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: SoundBankFile = ron::de::from_bytes(&bytes)?;
+
+        if file.variants.is_empty() {
+            return Err(SoundBankLoaderError::NoVariants);
+        }
+        if file.max_voices == Some(0) {
+            return Err(SoundBankLoaderError::NoVoices);
+        }
+
+        Ok(SoundBank {
+            variants: file
+                .variants
+                .iter()
+                .map(|path| load_context.load(path))
+                .collect(),
+            pitch: file.pitch,
+            volume: file.volume,
+            max_voices: file.max_voices,
+            when_full: file.when_full,
+            cooldown: Duration::from_secs_f32(file.cooldown.max(0.0)),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sound.ron"]
+    }
+}
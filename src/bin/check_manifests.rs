@@ -0,0 +1,96 @@
+//! Checks that every path listed in the `.assets.ron` files under
+//! `assets/manifests` exists.
+//!
+//! Run with `cargo run --bin check_manifests`. Exits with an error if a
+//! manifest can't be parsed or refers to a missing asset.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde::Deserialize;
+
+const ASSETS: &str = "assets";
+const MANIFESTS: &str = "assets/manifests";
+
+/// One entry of a manifest, as read by the game's manifest loader.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestEntry {
+    Path(String),
+    Paths(Vec<String>),
+}
+
+fn main() -> ExitCode {
+    let mut manifests = Vec::new();
+    if let Err(error) = find_manifests(Path::new(MANIFESTS), &mut manifests) {
+        eprintln!("Could not read {MANIFESTS}: {error}");
+        return ExitCode::FAILURE;
+    }
+    manifests.sort();
+
+    let mut problems = 0;
+    for manifest in &manifests {
+        problems += check_manifest(manifest);
+    }
+
+    if problems > 0 {
+        eprintln!("{problems} problem(s) in {} manifest(s)", manifests.len());
+        ExitCode::FAILURE
+    } else {
+        println!("All {} manifest(s) are fine", manifests.len());
+        ExitCode::SUCCESS
+    }
+}
+
+/// Collect every manifest in `dir` and its subfolders.
+fn find_manifests(dir: &Path, manifests: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_manifests(&path, manifests)?;
+        } else if path.to_string_lossy().ends_with(".assets.ron") {
+            manifests.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Print every problem with the manifest and return how many there were.
+fn check_manifest(manifest: &Path) -> usize {
+    let contents = match fs::read_to_string(manifest) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("{}: could not read: {error}", manifest.display());
+            return 1;
+        }
+    };
+    let entries: BTreeMap<String, ManifestEntry> = match ron::from_str(&contents) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("{}: could not parse: {error}", manifest.display());
+            return 1;
+        }
+    };
+
+    let mut problems = 0;
+    for (field, entry) in entries {
+        let paths = match entry {
+            ManifestEntry::Path(path) => vec![path],
+            ManifestEntry::Paths(paths) => paths,
+        };
+        for path in paths {
+            if !Path::new(ASSETS).join(&path).is_file() {
+                eprintln!(
+                    "{}: `{field}` refers to missing asset {path}",
+                    manifest.display()
+                );
+                problems += 1;
+            }
+        }
+    }
+    problems
+}
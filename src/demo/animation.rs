@@ -1,147 +1,585 @@
-//! Player sprite animation.
-//! This is based on multiple examples and may be very different for your game.
+//! Data-driven sprite animation.
+//! Animations are described in `.anim.ron` files (see `assets/animations/`) and
+//! hot-reload in native dev builds, so they can be retimed without recompiling.
+//! Frames come either from a sprite sheet or from a sequence of separate images,
+//! which are packed into a single atlas at load time. Clips can name events on
+//! specific frames, which are triggered as [`FrameEvent`]s on the animated entity.
 //! - [Sprite flipping](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
 //! - [Sprite animation](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_animation.rs)
-//! - [Timers](https://github.com/bevyengine/bevy/blob/latest/examples/time/timers.rs)
+//! - [Custom asset loaders](https://github.com/bevyengine/bevy/blob/latest/examples/asset/custom_asset.rs)
 
-use bevy::prelude::*;
 use std::time::Duration;
 
-use crate::demo::movement::MovementController;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadDirectError, io::Reader},
+    image::TextureAtlasBuilderError,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
 
-/// Registers the player animation component and systems.
+use crate::{
+    AppSystems, PausableSystems, asset_tracking::RegisterManifestAsset,
+    demo::movement::MovementController,
+};
+
+/// Registers the animation asset, its loader and the animator systems.
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<PlayerAnimation>();
+    app.init_asset::<SpriteAnimation>();
+    app.register_asset_loader(SpriteAnimationLoader);
+    app.register_manifest_asset::<SpriteAnimation>();
+
+    app.register_type::<SpriteAnimator>();
     app.add_systems(
         Update,
         (
-            update_animation_timer,
-            update_animation_movement,
-            update_animation_atlas,
-        ),
+            update_animation_movement.in_set(AppSystems::Update),
+            advance_animators
+                .in_set(AppSystems::Update)
+                .after(update_animation_movement),
+        )
+            .in_set(PausableSystems),
     );
 }
 
-/// Update the sprite direction and animation state (idling/walking).
+/// The clip played by a [`MovementController`] that is standing still.
+pub const IDLE_CLIP: &str = "idle";
+/// The clip played by a [`MovementController`] that is moving.
+pub const WALK_CLIP: &str = "walk";
+
+/// Update the sprite direction and animation clip (idling/walking).
 fn update_animation_movement(
-    mut player_query: Query<(&MovementController, &mut Sprite, &mut PlayerAnimation)>,
+    mut animator_query: Query<(&MovementController, &mut Sprite, &mut SpriteAnimator)>,
 ) {
-    for (controller, mut sprite, mut animation) in &mut player_query {
+    for (controller, mut sprite, mut animator) in &mut animator_query {
         let dx = controller.intent.x;
         if dx != 0.0 {
             sprite.flip_x = dx < 0.0;
         }
 
-        let animation_state = if controller.intent == Vec2::ZERO {
-            PlayerAnimationState::Idling
+        animator.play(if controller.intent == Vec2::ZERO {
+            IDLE_CLIP
         } else {
-            PlayerAnimationState::Walking
-        };
-        animation.update_state(animation_state);
-    }
-}
-
-/// Update the animation timer.
-fn update_animation_timer(time: Res<Time>, mut query: Query<&mut PlayerAnimation>) {
-    for mut animation in &mut query {
-        animation.update_timer(time.delta());
+            WALK_CLIP
+        });
     }
 }
 
-/// Update the texture atlas to reflect changes in the animation.
-fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut Sprite)>) {
-    for (animation, mut sprite) in &mut query {
-        let Some(atlas) = sprite.texture_atlas.as_mut() else {
+/// Step every animator forward, write the current frame to its sprite and
+/// trigger the events of every frame it entered.
+fn advance_animators(
+    time: Res<Time>,
+    mut commands: Commands,
+    animations: Res<Assets<SpriteAnimation>>,
+    mut animator_query: Query<(Entity, &mut SpriteAnimator, &mut Sprite)>,
+) {
+    for (entity, mut animator, mut sprite) in &mut animator_query {
+        let Some(animation) = animations.get(&animator.animation) else {
+            continue;
+        };
+        let Some(clip) = animation.clips.get(&animator.clip) else {
+            warn_once!("Unknown animation clip {:?}", animator.clip);
             continue;
         };
-        if animation.changed() {
-            atlas.index = animation.get_atlas_index();
+        for frame in animator.advance(clip, time.delta()) {
+            for name in &clip.events[frame] {
+                commands.trigger_targets(FrameEvent { name: name.clone() }, entity);
+            }
         }
+        animator.apply(animation, clip, &mut sprite);
     }
 }
 
-/// If the player is moving, play a step sound effect synchronized with the
-/// animation.
-// Step sound effect system removed for minimal debug setup.
-/// Component that tracks player's animation state.
-/// It is tightly bound to the texture atlas we use.
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-pub struct PlayerAnimation {
-    timer: Timer,
-    frame: usize,
-    state: PlayerAnimationState,
+/// Triggered on an animated entity when its clip reaches a frame with a named event.
+#[derive(Event, Debug, Clone)]
+pub struct FrameEvent {
+    pub name: String,
+}
+
+/// A sprite sheet animation loaded from a `.anim.ron` file.
+#[derive(Asset, TypePath, Debug)]
+pub struct SpriteAnimation {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: HashMap<String, SpriteClip>,
 }
 
-#[derive(Reflect, PartialEq)]
-pub enum PlayerAnimationState {
-    Idling,
-    Walking,
+/// A named sequence of atlas frames.
+#[derive(Debug, Clone)]
+pub struct SpriteClip {
+    /// Atlas indices, in playback order.
+    pub frames: Vec<usize>,
+    /// How long each frame is shown. Always the same length as `frames`.
+    pub durations: Vec<Duration>,
+    /// Names of the [`FrameEvent`]s triggered when each frame is reached.
+    /// Always the same length as `frames`.
+    pub events: Vec<Vec<String>>,
+    pub mode: ClipMode,
+}
+
+impl SpriteClip {
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
 }
 
-impl PlayerAnimation {
-    /// The number of idle frames.
-    const IDLE_FRAMES: usize = 2;
-    /// The duration of each idle frame.
-    const IDLE_INTERVAL: Duration = Duration::from_millis(500);
-    /// The number of walking frames.
-    const WALKING_FRAMES: usize = 6;
-    /// The duration of each walking frame.
-    const WALKING_INTERVAL: Duration = Duration::from_millis(50);
+/// What a clip does after its last frame.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipMode {
+    /// Start again from the first frame.
+    #[default]
+    Loop,
+    /// Stay on the last frame.
+    Once,
+}
+
+/// Plays named clips of a [`SpriteAnimation`] on the entity's [`Sprite`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Sprite)]
+pub struct SpriteAnimator {
+    pub animation: Handle<SpriteAnimation>,
+    clip: String,
+    /// Position within the clip, not the atlas index.
+    frame: usize,
+    /// Time spent on the current frame.
+    elapsed: Duration,
+    finished: bool,
+    /// Whether the first frame has been reached yet, so its events still need to fire.
+    started: bool,
+}
 
-    fn idling() -> Self {
+impl SpriteAnimator {
+    pub fn new(animation: Handle<SpriteAnimation>, clip: impl Into<String>) -> Self {
         Self {
-            timer: Timer::new(Self::IDLE_INTERVAL, TimerMode::Repeating),
+            animation,
+            clip: clip.into(),
             frame: 0,
-            state: PlayerAnimationState::Idling,
+            elapsed: Duration::ZERO,
+            finished: false,
+            started: false,
         }
     }
 
-    fn walking() -> Self {
-        Self {
-            timer: Timer::new(Self::WALKING_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Walking,
+    /// Switch to another clip, starting from its first frame.
+    /// Does nothing if the clip is already playing.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.restart(clip);
         }
     }
 
-    pub fn new() -> Self {
-        Self::idling()
+    /// Play a clip from its first frame, even if it's already playing.
+    pub fn restart(&mut self, clip: &str) {
+        self.clip = clip.to_string();
+        self.frame = 0;
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.started = false;
+    }
+
+    /// Step the clip forward and return the frames that were entered, in order.
+    fn advance(&mut self, clip: &SpriteClip, delta: Duration) -> Vec<usize> {
+        let mut entered = Vec::new();
+        // The clip may have shrunk after a hot reload.
+        if self.frame >= clip.len() {
+            self.frame = 0;
+            self.elapsed = Duration::ZERO;
+        }
+        if !self.started {
+            self.started = true;
+            entered.push(self.frame);
+        }
+        if self.finished {
+            return entered;
+        }
+
+        self.elapsed += delta;
+        while self.elapsed >= clip.durations[self.frame] {
+            self.elapsed -= clip.durations[self.frame];
+            if self.frame + 1 < clip.len() {
+                self.frame += 1;
+            } else if clip.mode == ClipMode::Loop {
+                self.frame = 0;
+            } else {
+                self.elapsed = Duration::ZERO;
+                self.finished = true;
+                break;
+            }
+            entered.push(self.frame);
+        }
+        entered
     }
 
-    /// Update animation timers.
-    pub fn update_timer(&mut self, delta: Duration) {
-        self.timer.tick(delta);
-        if !self.timer.finished() {
+    /// Write the current frame to the sprite, touching only what changed.
+    fn apply(&self, animation: &SpriteAnimation, clip: &SpriteClip, sprite: &mut Mut<Sprite>) {
+        let index = clip.frames[self.frame];
+        let up_to_date = sprite.image == animation.image
+            && sprite
+                .texture_atlas
+                .as_ref()
+                .is_some_and(|atlas| atlas.layout == animation.layout && atlas.index == index);
+        if up_to_date {
             return;
         }
-        self.frame = (self.frame + 1)
-            % match self.state {
-                PlayerAnimationState::Idling => Self::IDLE_FRAMES,
-                PlayerAnimationState::Walking => Self::WALKING_FRAMES,
-            };
-    }
-
-    /// Update animation state if it changes.
-    pub fn update_state(&mut self, state: PlayerAnimationState) {
-        if self.state != state {
-            match state {
-                PlayerAnimationState::Idling => *self = Self::idling(),
-                PlayerAnimationState::Walking => *self = Self::walking(),
+        sprite.image = animation.image.clone();
+        sprite.texture_atlas = Some(TextureAtlas {
+            layout: animation.layout.clone(),
+            index,
+        });
+    }
+}
+
+/// The on-disk format of a [`SpriteAnimation`].
+#[derive(Deserialize, Debug)]
+struct SpriteAnimationFile {
+    sheet: SheetFile,
+    clips: HashMap<String, SpriteClipFile>,
+}
+
+/// Where the frames of an animation come from.
+#[derive(Deserialize, Debug)]
+enum SheetFile {
+    /// A single image cut into a grid of equally sized cells.
+    Grid {
+        /// Path to the image, relative to the `assets` folder.
+        image: String,
+        /// Size of a single cell, in pixels.
+        tile_size: (u32, u32),
+        columns: u32,
+        rows: u32,
+        /// Gap between cells, in pixels.
+        #[serde(default)]
+        padding: Option<(u32, u32)>,
+        /// Distance from the image's top-left corner to the first cell, in pixels.
+        #[serde(default)]
+        offset: Option<(u32, u32)>,
+    },
+    /// Separate images packed into one atlas, in order. Atlas indices continue
+    /// from one sequence to the next.
+    Frames(Vec<FrameSequenceFile>),
+}
+
+/// A list of frame images, either numbered or given explicitly.
+///
+/// Asset loaders can't list directories on every platform (notably Wasm), so a
+/// "folder" of frames is described by a numbered file name pattern instead.
+#[derive(Deserialize, Debug)]
+enum FrameSequenceFile {
+    /// `pattern` with `{}` replaced by each number from `start` up to
+    /// `start + count`, zero-padded to `digits`. For example
+    /// `"images/entities/enemy/idle/{}.png"` with `digits: 2` gives `00.png`, `01.png`...
+    Numbered {
+        pattern: String,
+        count: usize,
+        #[serde(default)]
+        start: usize,
+        #[serde(default = "default_digits")]
+        digits: usize,
+    },
+    /// Paths relative to the `assets` folder.
+    Files(Vec<String>),
+}
+
+fn default_digits() -> usize {
+    1
+}
+
+impl FrameSequenceFile {
+    fn paths(&self) -> Vec<String> {
+        match self {
+            Self::Numbered {
+                pattern,
+                count,
+                start,
+                digits,
+            } => (*start..start + count)
+                .map(|i| pattern.replace("{}", &format!("{i:0digits$}")))
+                .collect(),
+            Self::Files(files) => files.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SpriteClipFile {
+    frames: FramesFile,
+    durations: DurationsFile,
+    #[serde(default)]
+    mode: ClipMode,
+    /// Event names to trigger, keyed by position within the clip (not atlas index).
+    #[serde(default)]
+    events: HashMap<usize, Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+enum FramesFile {
+    /// Atlas indices from `start` (inclusive) to `end` (exclusive).
+    Range(usize, usize),
+    /// Atlas indices in playback order.
+    List(Vec<usize>),
+}
+
+#[derive(Deserialize, Debug)]
+enum DurationsFile {
+    /// Every frame lasts this many milliseconds.
+    Uniform(u64),
+    /// One duration in milliseconds per frame.
+    PerFrame(Vec<u64>),
+}
+
+#[derive(Default)]
+struct SpriteAnimationLoader;
+
+#[derive(Debug, Error)]
+pub enum SpriteAnimationLoaderError {
+    #[error("Could not read animation file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse animation file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Could not load animation frame: {0}")]
+    Frame(#[from] Box<LoadDirectError>),
+    #[error("Could not pack animation frames into an atlas: {0}")]
+    Atlas(#[from] TextureAtlasBuilderError),
+    #[error("Animation has no frames")]
+    NoFrames,
+    #[error("Clip {clip:?} has no frames")]
+    EmptyClip { clip: String },
+    #[error("Clip {clip:?} uses frame {frame}, but the sheet only has {count} frames")]
+    FrameOutOfRange {
+        clip: String,
+        frame: usize,
+        count: usize,
+    },
+    #[error("Clip {clip:?} has {frames} frames but {durations} durations")]
+    DurationCountMismatch {
+        clip: String,
+        frames: usize,
+        durations: usize,
+    },
+    #[error("Clip {clip:?} has a zero-length frame")]
+    ZeroDuration { clip: String },
+    #[error("Clip {clip:?} has an event on frame {frame}, but only {frames} frames")]
+    EventOutOfRange {
+        clip: String,
+        frame: usize,
+        frames: usize,
+    },
+}
+
+impl AssetLoader for SpriteAnimationLoader {
+    type Asset = SpriteAnimation;
+    type Settings = ();
+    type Error = SpriteAnimationLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: SpriteAnimationFile = ron::de::from_bytes(&bytes)?;
+
+        let (image, layout) = match &file.sheet {
+            SheetFile::Grid {
+                image,
+                tile_size,
+                columns,
+                rows,
+                padding,
+                offset,
+            } => {
+                let layout = TextureAtlasLayout::from_grid(
+                    UVec2::from(*tile_size),
+                    *columns,
+                    *rows,
+                    padding.map(UVec2::from),
+                    offset.map(UVec2::from),
+                );
+                (load_context.load(image), layout)
+            }
+            SheetFile::Frames(sequences) => {
+                let (image, layout) = pack_frames(sequences, load_context).await?;
+                (
+                    load_context.add_labeled_asset("image".to_string(), image),
+                    layout,
+                )
             }
+        };
+        let frame_count = layout.len();
+        let layout = load_context.add_labeled_asset("layout".to_string(), layout);
+
+        let mut clips = HashMap::default();
+        for (name, clip) in file.clips {
+            let clip = parse_clip(&name, clip, frame_count)?;
+            clips.insert(name, clip);
         }
+
+        Ok(SpriteAnimation {
+            image,
+            layout,
+            clips,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+/// Load every frame image and pack them into one atlas. The atlas index of each
+/// frame is its position in the sequences.
+async fn pack_frames(
+    sequences: &[FrameSequenceFile],
+    load_context: &mut LoadContext<'_>,
+) -> Result<(Image, TextureAtlasLayout), SpriteAnimationLoaderError> {
+    let mut frames = Vec::new();
+    for path in sequences.iter().flat_map(FrameSequenceFile::paths) {
+        // Loading immediately also registers the frame as a dependency, so
+        // editing it hot-reloads the whole animation.
+        let frame = load_context
+            .loader()
+            .immediate()
+            .load::<Image>(path)
+            .await
+            .map_err(Box::new)?;
+        frames.push(frame.take());
+    }
+    pack_images(&frames)
+}
+
+/// Pack `frames` into one atlas, keeping them in order.
+fn pack_images(
+    frames: &[Image],
+) -> Result<(Image, TextureAtlasLayout), SpriteAnimationLoaderError> {
+    if frames.is_empty() {
+        return Err(SpriteAnimationLoaderError::NoFrames);
+    }
+
+    let mut builder = TextureAtlasBuilder::default();
+    for frame in frames {
+        // `TextureAtlasBuilder` keeps insertion order, so no ids are needed.
+        builder.add_texture(None, frame);
+    }
+    let (layout, _sources, image) = builder.build()?;
+    Ok((image, layout))
+}
+
+fn parse_clip(
+    name: &str,
+    clip: SpriteClipFile,
+    frame_count: usize,
+) -> Result<SpriteClip, SpriteAnimationLoaderError> {
+    let frames: Vec<usize> = match clip.frames {
+        FramesFile::Range(start, end) => (start..end).collect(),
+        FramesFile::List(frames) => frames,
+    };
+    if frames.is_empty() {
+        return Err(SpriteAnimationLoaderError::EmptyClip {
+            clip: name.to_string(),
+        });
+    }
+    if let Some(&frame) = frames.iter().find(|&&frame| frame >= frame_count) {
+        return Err(SpriteAnimationLoaderError::FrameOutOfRange {
+            clip: name.to_string(),
+            frame,
+            count: frame_count,
+        });
+    }
+
+    let durations = match clip.durations {
+        DurationsFile::Uniform(millis) => vec![millis; frames.len()],
+        DurationsFile::PerFrame(durations) => durations,
+    };
+    if durations.len() != frames.len() {
+        return Err(SpriteAnimationLoaderError::DurationCountMismatch {
+            clip: name.to_string(),
+            frames: frames.len(),
+            durations: durations.len(),
+        });
+    }
+    if durations.contains(&0) {
+        return Err(SpriteAnimationLoaderError::ZeroDuration {
+            clip: name.to_string(),
+        });
     }
 
-    /// Whether animation changed this tick.
-    pub fn changed(&self) -> bool {
-        self.timer.finished()
+    let mut events = vec![Vec::new(); frames.len()];
+    for (frame, names) in clip.events {
+        let Some(frame_events) = events.get_mut(frame) else {
+            return Err(SpriteAnimationLoaderError::EventOutOfRange {
+                clip: name.to_string(),
+                frame,
+                frames: frames.len(),
+            });
+        };
+        frame_events.extend(names);
     }
 
-    /// Return sprite index in the atlas.
-    pub fn get_atlas_index(&self) -> usize {
-        match self.state {
-            PlayerAnimationState::Idling => self.frame,
-            PlayerAnimationState::Walking => 6 + self.frame,
+    Ok(SpriteClip {
+        frames,
+        durations: durations.into_iter().map(Duration::from_millis).collect(),
+        events,
+        mode: clip.mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+
+    fn frame(width: u32, height: u32) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+
+    #[test]
+    fn numbers_frame_paths() {
+        let sequence = FrameSequenceFile::Numbered {
+            pattern: "idle/{}.png".to_string(),
+            count: 3,
+            start: 9,
+            digits: 2,
+        };
+        assert_eq!(
+            sequence.paths(),
+            ["idle/09.png", "idle/10.png", "idle/11.png"]
+        );
+    }
+
+    #[test]
+    fn packs_frames_in_order() {
+        let frames = [frame(4, 4), frame(8, 2), frame(2, 6)];
+        let (image, layout) = pack_images(&frames).unwrap();
+        assert_eq!(layout.len(), frames.len());
+        for (rect, frame) in layout.textures.iter().zip(&frames) {
+            assert_eq!(rect.size(), frame.size());
+            assert!(rect.max.cmple(image.size()).all());
         }
     }
+
+    #[test]
+    fn rejects_empty_sequences() {
+        assert!(matches!(
+            pack_images(&[]),
+            Err(SpriteAnimationLoaderError::NoFrames)
+        ));
+    }
 }
@@ -0,0 +1,226 @@
+//! Keep entities inside the level.
+//!
+//! The bounds come from the size of the loaded [`TiledMap`]. Each edge can block,
+//! wrap around to the opposite edge or act as a kill plane, set per map with the
+//! string properties `edge_left`, `edge_right`, `edge_top` and `edge_bottom`
+//! (`"block"`, `"wrap"` or `"kill"`). Edges without a property block.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{
+        health::{DamageEvent, Health},
+        helpers::tiled::{TiledMap, TiledMapHandle},
+        movement::apply_movement,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LevelBounds>();
+    app.register_type::<StayInLevel>();
+    app.add_systems(
+        Update,
+        (
+            update_level_bounds,
+            apply_level_bounds.after(apply_movement),
+        ),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), remove_level_bounds);
+}
+
+/// What happens to an entity that crosses an edge of the level.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeBehavior {
+    /// Stop at the edge.
+    #[default]
+    Block,
+    /// Come back in through the opposite edge.
+    Wrap,
+    /// Die, or despawn if the entity has no [`Health`].
+    Kill,
+}
+
+impl EdgeBehavior {
+    fn from_property(value: &str) -> Option<Self> {
+        match value {
+            "block" => Some(Self::Block),
+            "wrap" => Some(Self::Wrap),
+            "kill" => Some(Self::Kill),
+            _ => None,
+        }
+    }
+}
+
+/// The playable area of the current level, in world coordinates.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct LevelBounds {
+    pub rect: Rect,
+    pub left: EdgeBehavior,
+    pub right: EdgeBehavior,
+    pub top: EdgeBehavior,
+    pub bottom: EdgeBehavior,
+}
+
+impl LevelBounds {
+    fn from_map(tiled_map: &TiledMap, offset: Vec2) -> Self {
+        let edge = |name: &str| {
+            let Some(value) = tiled_map.string_property(name) else {
+                return EdgeBehavior::default();
+            };
+            EdgeBehavior::from_property(value).unwrap_or_else(|| {
+                warn!("Unknown level edge behavior `{name} = {value}`, using `block`");
+                EdgeBehavior::default()
+            })
+        };
+        let rect = tiled_map.world_rect();
+        Self {
+            rect: Rect::from_corners(rect.min + offset, rect.max + offset),
+            left: edge("edge_left"),
+            right: edge("edge_right"),
+            top: edge("edge_top"),
+            bottom: edge("edge_bottom"),
+        }
+    }
+}
+
+/// Confines an entity to the [`LevelBounds`].
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct StayInLevel;
+
+fn update_level_bounds(
+    mut commands: Commands,
+    map_query: Query<(&TiledMapHandle, &GlobalTransform)>,
+    maps: Res<Assets<TiledMap>>,
+    level_bounds: Option<Res<LevelBounds>>,
+) {
+    let Some(bounds) = map_query.iter().find_map(|(handle, map_transform)| {
+        let tiled_map = maps.get(&handle.0)?;
+        Some(LevelBounds::from_map(
+            tiled_map,
+            map_transform.translation().truncate(),
+        ))
+    }) else {
+        return;
+    };
+    if level_bounds.is_none_or(|level_bounds| *level_bounds != bounds) {
+        commands.insert_resource(bounds);
+    }
+}
+
+fn remove_level_bounds(mut commands: Commands) {
+    commands.remove_resource::<LevelBounds>();
+}
+
+fn apply_level_bounds(
+    mut commands: Commands,
+    level_bounds: Option<Res<LevelBounds>>,
+    mut bounded_query: Query<(Entity, &mut Transform, Has<Health>), With<StayInLevel>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let Some(bounds) = level_bounds else {
+        return;
+    };
+    let rect = bounds.rect;
+    for (entity, mut transform, has_health) in &mut bounded_query {
+        let position = transform.translation.truncate();
+        let (x, x_killed) = confine(
+            position.x,
+            rect.min.x,
+            rect.max.x,
+            bounds.left,
+            bounds.right,
+        );
+        let (y, y_killed) = confine(
+            position.y,
+            rect.min.y,
+            rect.max.y,
+            bounds.bottom,
+            bounds.top,
+        );
+        transform.translation = Vec2::new(x, y).extend(transform.translation.z);
+
+        if x_killed || y_killed {
+            if has_health {
+                damage_events.write(DamageEvent::lethal(entity));
+            } else {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Apply the edge behaviors to one axis. Returns the new coordinate and
+/// whether the entity crossed a kill plane.
+fn confine(
+    value: f32,
+    min: f32,
+    max: f32,
+    below: EdgeBehavior,
+    above: EdgeBehavior,
+) -> (f32, bool) {
+    let (edge, limit) = if value < min {
+        (below, min)
+    } else if value > max {
+        (above, max)
+    } else {
+        return (value, false);
+    };
+    match edge {
+        EdgeBehavior::Block => (limit, false),
+        EdgeBehavior::Wrap => (min + (value - min).rem_euclid(max - min), false),
+        // Hold the entity at the edge until the death and respawn flow moves it.
+        EdgeBehavior::Kill => (limit, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use EdgeBehavior::*;
+
+    #[test]
+    fn inside_is_untouched() {
+        for edge in [Block, Wrap, Kill] {
+            assert_eq!(confine(5.0, 0.0, 10.0, edge, edge), (5.0, false));
+        }
+        assert_eq!(confine(0.0, 0.0, 10.0, Kill, Kill), (0.0, false));
+        assert_eq!(confine(10.0, 0.0, 10.0, Kill, Kill), (10.0, false));
+    }
+
+    #[test]
+    fn block_stops_at_the_edge() {
+        assert_eq!(confine(-3.0, 0.0, 10.0, Block, Kill), (0.0, false));
+        assert_eq!(confine(12.0, 0.0, 10.0, Kill, Block), (10.0, false));
+    }
+
+    #[test]
+    fn wrap_comes_back_through_the_opposite_edge() {
+        assert_eq!(confine(-3.0, 0.0, 10.0, Wrap, Block), (7.0, false));
+        assert_eq!(confine(12.0, 0.0, 10.0, Block, Wrap), (2.0, false));
+        assert_eq!(confine(-25.0, -10.0, 10.0, Wrap, Wrap), (-5.0, false));
+    }
+
+    #[test]
+    fn kill_holds_at_the_edge_and_reports_it() {
+        assert_eq!(confine(-3.0, 0.0, 10.0, Kill, Block), (0.0, true));
+        assert_eq!(confine(12.0, 0.0, 10.0, Block, Kill), (10.0, true));
+    }
+
+    #[test]
+    fn each_edge_uses_its_own_behavior() {
+        assert_eq!(confine(-1.0, 0.0, 10.0, Block, Kill), (0.0, false));
+        assert_eq!(confine(11.0, 0.0, 10.0, Block, Kill), (10.0, true));
+    }
+
+    #[test]
+    fn parses_edge_properties() {
+        assert_eq!(EdgeBehavior::from_property("block"), Some(Block));
+        assert_eq!(EdgeBehavior::from_property("wrap"), Some(Wrap));
+        assert_eq!(EdgeBehavior::from_property("kill"), Some(Kill));
+        assert_eq!(EdgeBehavior::from_property("Kill"), None);
+    }
+}
@@ -0,0 +1,298 @@
+//! A camera rig that follows the player through the level.
+//!
+//! The rig keeps the [`CameraTarget`] inside a dead zone, leads it in the
+//! direction it's moving and eases towards that point. The view is clamped to
+//! the bounds of the current [`TiledMap`], or to a camera zone if the target is
+//! standing in one.
+//!
+//! Camera zones are rectangle objects with the class [`CAMERA_ZONE_CLASS`] in
+//! any object layer of the map. Use them to keep the view inside a single room.
+//!
+//! Screen shake is layered on top of the rig's position rather than written
+//! into it, so shaking never makes the camera drift. Shake the screen with
+//! `commands.trigger(AddTrauma(0.5))`.
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::{
+    accessibility::Accessibility,
+    demo::{
+        helpers::tiled::{TiledMap, TiledMapHandle},
+        movement::MovementController,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CameraRig>();
+    app.register_type::<CameraTarget>();
+    app.register_type::<CameraShake>();
+    app.add_observer(add_trauma);
+
+    app.add_systems(OnEnter(Screen::Gameplay), attach_camera_rig);
+    app.add_systems(OnExit(Screen::Gameplay), detach_camera_rig);
+    app.add_systems(
+        PostUpdate,
+        (update_camera_shake, follow_camera_target)
+            .chain()
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// The Tiled object class that marks a camera zone.
+pub const CAMERA_ZONE_CLASS: &str = "CameraZone";
+
+/// The entity the [`CameraRig`] follows.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct CameraTarget;
+
+/// Makes a camera follow the [`CameraTarget`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(CameraShake)]
+pub struct CameraRig {
+    /// Half the size of the area around the camera's focus in which the target
+    /// can move without the camera following, in world units.
+    pub dead_zone: Vec2,
+    /// How far ahead of the target the camera looks when it moves, in world units.
+    pub look_ahead: Vec2,
+    /// How quickly the camera catches up with its focus. Higher is snappier.
+    pub follow_decay_rate: f32,
+    /// How quickly the look-ahead swings around when the target turns.
+    pub look_ahead_decay_rate: f32,
+    /// The point the dead zone is centered on. `None` until the rig has
+    /// found its target, at which point it snaps straight to it.
+    focus: Option<Vec2>,
+    look_ahead_offset: Vec2,
+    /// The camera position before any effects are layered on top.
+    position: Vec2,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            dead_zone: Vec2::new(48.0, 32.0),
+            look_ahead: Vec2::new(64.0, 32.0),
+            follow_decay_rate: 6.0,
+            look_ahead_decay_rate: 2.0,
+            focus: None,
+            look_ahead_offset: Vec2::ZERO,
+            position: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraRig {
+    /// Jump straight to the target next frame instead of easing towards it,
+    /// e.g. after it teleports.
+    pub fn snap(&mut self) {
+        self.focus = None;
+    }
+}
+
+/// Trauma-based screen shake for a [`CameraRig`].
+///
+/// Trauma goes from 0 to 1 and decays over time. The shake grows with the
+/// square of the trauma, so small hits barely register while big ones are
+/// violent.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct CameraShake {
+    pub trauma: f32,
+    /// Trauma lost per second.
+    pub decay: f32,
+    /// The largest offset at full trauma, in world units.
+    pub max_offset: Vec2,
+    /// The largest rotation at full trauma, in radians.
+    pub max_angle: f32,
+    /// How fast the shake moves, in noise samples per second.
+    pub frequency: f32,
+    offset: Vec2,
+    angle: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: Vec2::splat(24.0),
+            max_angle: 3_f32.to_radians(),
+            frequency: 25.0,
+            offset: Vec2::ZERO,
+            angle: 0.0,
+        }
+    }
+}
+
+/// Add trauma to every [`CameraShake`]. Ignored when reduced motion is on.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AddTrauma(pub f32);
+
+fn add_trauma(
+    trigger: Trigger<AddTrauma>,
+    accessibility: Res<Accessibility>,
+    mut shake_query: Query<&mut CameraShake>,
+) {
+    if accessibility.reduced_motion {
+        return;
+    }
+    for mut shake in &mut shake_query {
+        shake.trauma = (shake.trauma + trigger.event().0).clamp(0.0, 1.0);
+    }
+}
+
+fn update_camera_shake(
+    // Keep shaking through hit-stops, which slow down virtual time.
+    time: Res<Time<Real>>,
+    accessibility: Res<Accessibility>,
+    mut shake_query: Query<&mut CameraShake>,
+) {
+    let t = time.elapsed_secs();
+    for mut shake in &mut shake_query {
+        if accessibility.reduced_motion {
+            shake.trauma = 0.0;
+        }
+        shake.trauma = (shake.trauma - shake.decay * time.delta_secs()).max(0.0);
+
+        let amount = shake.trauma * shake.trauma;
+        let t = t * shake.frequency;
+        shake.offset = amount * shake.max_offset * Vec2::new(noise(0, t), noise(1, t));
+        shake.angle = amount * shake.max_angle * noise(2, t);
+    }
+}
+
+/// Smooth 1D value noise in `[-1, 1]`. Each `seed` gives an independent curve.
+fn noise(seed: u32, t: f32) -> f32 {
+    let i = t.floor();
+    let fraction = t - i;
+    let i = i as i32 as u32;
+    let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+    lattice(seed, i).lerp(lattice(seed, i.wrapping_add(1)), smooth)
+}
+
+/// A pseudo-random value in `[-1, 1]` for each integer point.
+fn lattice(seed: u32, i: u32) -> f32 {
+    let mut x = i.wrapping_mul(0x9E37_79B1) ^ seed.wrapping_mul(0x85EB_CA77);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2C1B_3C6D);
+    x ^= x >> 12;
+    x as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+fn attach_camera_rig(mut commands: Commands, camera: Single<Entity, With<Camera2d>>) {
+    commands.entity(*camera).insert(CameraRig::default());
+}
+
+fn detach_camera_rig(
+    mut commands: Commands,
+    camera: Single<(Entity, &mut Transform), With<CameraRig>>,
+) {
+    let (entity, mut transform) = camera.into_inner();
+    commands.entity(entity).remove::<(CameraRig, CameraShake)>();
+    transform.translation = Vec3::ZERO.with_z(transform.translation.z);
+    transform.rotation = Quat::IDENTITY;
+}
+
+fn follow_camera_target(
+    time: Res<Time>,
+    transform_helper: TransformHelper,
+    target_query: Query<(Entity, Option<&MovementController>), With<CameraTarget>>,
+    map_query: Query<(&TiledMapHandle, &GlobalTransform)>,
+    maps: Res<Assets<TiledMap>>,
+    mut camera_query: Query<(&mut CameraRig, &CameraShake, &mut Transform, &Projection)>,
+) {
+    let Ok((target, controller)) = target_query.single() else {
+        return;
+    };
+    // The target may have moved this frame, so don't rely on last frame's `GlobalTransform`.
+    let Ok(target_transform) = transform_helper.compute_global_transform(target) else {
+        return;
+    };
+    let target_position = target_transform.translation().truncate();
+    let bounds = camera_bounds(target_position, &map_query, &maps);
+
+    for (mut rig, shake, mut transform, projection) in &mut camera_query {
+        let Some(focus) = rig.focus else {
+            rig.focus = Some(target_position);
+            rig.look_ahead_offset = Vec2::ZERO;
+            rig.position = clamp_view(target_position, bounds, projection);
+            apply_rig(&rig, shake, &mut transform);
+            continue;
+        };
+
+        // Drag the dead zone along with the target when it reaches an edge.
+        let focus = focus.clamp(
+            target_position - rig.dead_zone,
+            target_position + rig.dead_zone,
+        );
+        rig.focus = Some(focus);
+
+        let heading = controller.map_or(Vec2::ZERO, |controller| controller.intent);
+        let look_ahead = heading * rig.look_ahead;
+        let look_ahead_decay_rate = rig.look_ahead_decay_rate;
+        rig.look_ahead_offset
+            .smooth_nudge(&look_ahead, look_ahead_decay_rate, time.delta_secs());
+
+        let goal = clamp_view(focus + rig.look_ahead_offset, bounds, projection);
+        let follow_decay_rate = rig.follow_decay_rate;
+        rig.position
+            .smooth_nudge(&goal, follow_decay_rate, time.delta_secs());
+        apply_rig(&rig, shake, &mut transform);
+    }
+}
+
+fn apply_rig(rig: &CameraRig, shake: &CameraShake, transform: &mut Transform) {
+    transform.translation = (rig.position + shake.offset).extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(shake.angle);
+}
+
+/// The area the camera must stay inside: the camera zone containing `point`,
+/// falling back to the whole map.
+fn camera_bounds(
+    point: Vec2,
+    map_query: &Query<(&TiledMapHandle, &GlobalTransform)>,
+    maps: &Assets<TiledMap>,
+) -> Option<Rect> {
+    map_query.iter().find_map(|(handle, map_transform)| {
+        let tiled_map = maps.get(&handle.0)?;
+        let offset = map_transform.translation().truncate();
+        let world_rect = tiled_map.world_rect();
+        let map_rect = Rect::from_corners(world_rect.min + offset, world_rect.max + offset);
+        if !map_rect.contains(point) {
+            return None;
+        }
+
+        let zone = tiled_map
+            .objects(CAMERA_ZONE_CLASS)
+            .filter_map(|object| tiled_map.object_rect(&object))
+            .map(|rect| Rect::from_corners(rect.min + offset, rect.max + offset))
+            .find(|rect| rect.contains(point));
+        Some(zone.unwrap_or(map_rect))
+    })
+}
+
+/// Move the camera center so its view stays inside `bounds`. If the view is
+/// larger than the bounds on an axis, the bounds are centered on that axis.
+fn clamp_view(center: Vec2, bounds: Option<Rect>, projection: &Projection) -> Vec2 {
+    let (Some(bounds), Projection::Orthographic(orthographic)) = (bounds, projection) else {
+        return center;
+    };
+    let half_view = orthographic.area.half_size();
+    let min = bounds.min + half_view;
+    let max = bounds.max - half_view;
+    let bounds_center = bounds.center();
+    Vec2::new(
+        if min.x <= max.x {
+            center.x.clamp(min.x, max.x)
+        } else {
+            bounds_center.x
+        },
+        if min.y <= max.y {
+            center.y.clamp(min.y, max.y)
+        } else {
+            bounds_center.y
+        },
+    )
+}
@@ -0,0 +1,149 @@
+//! Checkpoints, where the player comes back to after dying.
+//!
+//! Checkpoints are rectangle objects of the class [`CHECKPOINT_CLASS`] in any
+//! object layer of the map. Walking into one lights it up, moves the
+//! [`RespawnPoint`] to it and saves the game, so loading the save continues
+//! from the checkpoint too. Only the most recently touched checkpoint is lit.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{SoundBank, SoundPosition, sound_effect_from_bank},
+    demo::{
+        player::{Player, RespawnPoint},
+        progression::LevelProgression,
+    },
+    particles::{ParticleEffect, particle_emitter},
+    save::save_game,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Checkpoint>();
+    app.register_type::<ActiveCheckpoint>();
+    app.register_type::<CheckpointAssets>();
+    app.load_resource::<CheckpointAssets>("manifests/checkpoint.assets.ron");
+
+    app.add_observer(light_checkpoint);
+    app.add_observer(dim_checkpoint);
+    app.add_systems(
+        Update,
+        activate_checkpoints
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The Tiled object class that marks a checkpoint. Its name doubles as a spawn
+/// point name, so saves can continue from it.
+pub const CHECKPOINT_CLASS: &str = "Checkpoint";
+
+/// Touching this makes it the place the player respawns.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Checkpoint {
+    /// The name of the Tiled object, used as the level's entry point.
+    pub name: String,
+    /// The size of the checkpoint, centered on its entity.
+    pub size: Vec2,
+}
+
+/// Marks the checkpoint the player will respawn at.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct ActiveCheckpoint;
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct CheckpointAssets {
+    #[dependency]
+    sprite: Handle<Image>,
+    #[dependency]
+    activate: Handle<SoundBank>,
+    #[dependency]
+    activate_particles: Handle<ParticleEffect>,
+}
+
+const INACTIVE_COLOR: Color = Color::srgb(0.35, 0.35, 0.4);
+
+/// A checkpoint covering `rect`, which is relative to its parent.
+pub fn checkpoint(checkpoint_assets: &CheckpointAssets, name: String, rect: Rect) -> impl Bundle {
+    (
+        Name::new("Checkpoint"),
+        Checkpoint {
+            name,
+            size: rect.size(),
+        },
+        Sprite {
+            image: checkpoint_assets.sprite.clone(),
+            color: INACTIVE_COLOR,
+            ..default()
+        },
+        Transform::from_translation(rect.center().extend(1.0))
+            .with_scale(Vec2::splat(2.0).extend(1.0)),
+    )
+}
+
+fn light_checkpoint(
+    trigger: Trigger<OnAdd, ActiveCheckpoint>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    if let Ok(mut sprite) = sprite_query.get_mut(trigger.target()) {
+        sprite.color = Color::WHITE;
+    }
+}
+
+fn dim_checkpoint(
+    trigger: Trigger<OnRemove, ActiveCheckpoint>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    if let Ok(mut sprite) = sprite_query.get_mut(trigger.target()) {
+        sprite.color = INACTIVE_COLOR;
+    }
+}
+
+fn activate_checkpoints(
+    mut commands: Commands,
+    checkpoint_assets: Option<Res<CheckpointAssets>>,
+    mut progression: ResMut<LevelProgression>,
+    mut respawn_point: ResMut<RespawnPoint>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    checkpoint_query: Query<(Entity, &Checkpoint, &GlobalTransform), Without<ActiveCheckpoint>>,
+    active_query: Query<Entity, With<ActiveCheckpoint>>,
+) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+    let position = player.translation().truncate();
+    let Some((entity, checkpoint, transform)) =
+        checkpoint_query.iter().find(|(_, checkpoint, transform)| {
+            Rect::from_center_size(transform.translation().truncate(), checkpoint.size)
+                .contains(position)
+        })
+    else {
+        return;
+    };
+
+    for active in &active_query {
+        commands.entity(active).remove::<ActiveCheckpoint>();
+    }
+    commands.entity(entity).insert(ActiveCheckpoint);
+    respawn_point.0 = transform.translation().truncate();
+    progression.entry = Some(checkpoint.name.clone());
+
+    if let Some(checkpoint_assets) = checkpoint_assets {
+        commands.spawn((
+            sound_effect_from_bank(checkpoint_assets.activate.clone()),
+            SoundPosition::At(transform.translation().truncate()),
+        ));
+        commands.spawn((
+            particle_emitter(checkpoint_assets.activate_particles.clone()),
+            Transform::from_translation(transform.translation()),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+    commands.queue(save_game);
+}
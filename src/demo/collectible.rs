@@ -0,0 +1,113 @@
+//! Collectibles, picked up by walking into them.
+//!
+//! Collectibles are point objects of the class [`COLLECTIBLE_CLASS`] in any
+//! object layer of the map. Picking one up adds its id to the
+//! [`CollectedItems`], which are saved, so it stays gone for the rest of the
+//! game, and triggers [`ItemCollected`].
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{SoundBank, SoundPosition, sound_effect_from_bank},
+    demo::{player::Player, progression::CollectedItems},
+    particles::{ParticleEffect, particle_emitter},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Collectible>();
+    app.register_type::<CollectibleAssets>();
+    app.load_resource::<CollectibleAssets>("manifests/collectible.assets.ron");
+
+    app.add_systems(
+        Update,
+        pick_up_collectibles
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The Tiled object class that marks a collectible.
+pub const COLLECTIBLE_CLASS: &str = "Collectible";
+
+/// How close the player has to get to a collectible to pick it up.
+const PICKUP_RADIUS: f32 = 16.0;
+
+/// An item the player can pick up.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Collectible {
+    /// The id recorded in [`CollectedItems`], unique across every level.
+    pub id: String,
+}
+
+/// Triggered when the player picks up a collectible.
+#[derive(Event, Debug)]
+pub struct ItemCollected;
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct CollectibleAssets {
+    #[dependency]
+    sprite: Handle<Image>,
+    #[dependency]
+    pickup: Handle<SoundBank>,
+    #[dependency]
+    pickup_particles: Handle<ParticleEffect>,
+}
+
+/// The id of the collectible with the Tiled object id `object_id` in `level`.
+pub fn collectible_id(level: &str, object_id: u32) -> String {
+    format!("{level}#{object_id}")
+}
+
+/// A collectible at `position`, which is relative to its parent.
+pub fn collectible(
+    collectible_assets: &CollectibleAssets,
+    id: String,
+    position: Vec2,
+) -> impl Bundle {
+    (
+        Name::new("Collectible"),
+        Collectible { id },
+        Sprite::from_image(collectible_assets.sprite.clone()),
+        Transform::from_translation(position.extend(1.0)),
+    )
+}
+
+fn pick_up_collectibles(
+    mut commands: Commands,
+    collectible_assets: Option<Res<CollectibleAssets>>,
+    mut collected: ResMut<CollectedItems>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    collectible_query: Query<(Entity, &Collectible, &GlobalTransform)>,
+) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+    let position = player.translation().truncate();
+    for (entity, collectible, transform) in &collectible_query {
+        let translation = transform.translation();
+        if translation.truncate().distance(position) > PICKUP_RADIUS {
+            continue;
+        }
+        collected.0.insert(collectible.id.clone());
+        commands.entity(entity).despawn();
+        commands.trigger(ItemCollected);
+
+        if let Some(collectible_assets) = &collectible_assets {
+            commands.spawn((
+                sound_effect_from_bank(collectible_assets.pickup.clone()),
+                SoundPosition::At(translation.truncate()),
+            ));
+            commands.spawn((
+                particle_emitter(collectible_assets.pickup_particles.clone()),
+                Transform::from_translation(translation),
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Enemies placed in Tiled.
+//!
+//! Enemies are point objects of the class [`ENEMY_CLASS`] in any object layer
+//! of the map. They stand guard where they're placed and hurt the player on
+//! contact. Enemies the player defeats come back when the player respawns.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource,
+    demo::{
+        animation::{IDLE_CLIP, SpriteAnimation, SpriteAnimator},
+        health::{ContactDamage, Health, Hurtbox, Team},
+        respawn::ResetOnRespawn,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Enemy>();
+    app.register_type::<EnemyAssets>();
+    app.load_resource::<EnemyAssets>("manifests/enemy.assets.ron");
+}
+
+/// The Tiled object class that marks where an enemy stands.
+pub const ENEMY_CLASS: &str = "Enemy";
+
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct Enemy;
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct EnemyAssets {
+    #[dependency]
+    animation: Handle<SpriteAnimation>,
+}
+
+const ENEMY_MAX_HEALTH: u32 = 3;
+const ENEMY_CONTACT_DAMAGE: u32 = 1;
+const ENEMY_KNOCKBACK: f32 = 400.0;
+/// The enemy's body, in world units (before scaling).
+const ENEMY_SIZE: Vec2 = Vec2::new(14.0, 18.0);
+
+/// An enemy standing at `position`, which is relative to its parent.
+pub fn enemy(enemy_assets: &EnemyAssets, position: Vec2) -> impl Bundle {
+    (
+        Name::new("Enemy"),
+        Enemy,
+        SpriteAnimator::new(enemy_assets.animation.clone(), IDLE_CLIP),
+        Transform::from_translation(position.extend(1.0)).with_scale(Vec2::splat(2.0).extend(1.0)),
+        Health::new(ENEMY_MAX_HEALTH),
+        Hurtbox { size: ENEMY_SIZE },
+        ContactDamage {
+            amount: ENEMY_CONTACT_DAMAGE,
+            knockback: ENEMY_KNOCKBACK,
+            size: ENEMY_SIZE,
+            despawn_on_hit: false,
+        },
+        Team::Enemy,
+        ResetOnRespawn::Restore,
+    )
+}
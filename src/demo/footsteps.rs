@@ -0,0 +1,108 @@
+//! Footstep sounds and dust, synchronized with any animation that triggers a
+//! `"footstep"` [`FrameEvent`]. Entities that are [`Airborne`] skip their steps.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource,
+    audio::{Positional, SoundBank, SoundPosition, sound_effect_from_bank},
+    demo::{animation::FrameEvent, movement::Airborne},
+    particles::{ParticleEffect, particle_emitter},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FootstepAssets>();
+    app.load_resource::<FootstepAssets>("manifests/footsteps.assets.ron");
+    app.add_observer(play_footstep_sound_effect);
+}
+
+/// The [`FrameEvent`] name that plays a footstep.
+pub const FOOTSTEP_EVENT: &str = "footstep";
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct FootstepAssets {
+    #[dependency]
+    steps: Handle<SoundBank>,
+    #[dependency]
+    dust: Handle<ParticleEffect>,
+}
+
+/// Footsteps are quiet, so they fade out much closer than other sounds.
+const FOOTSTEP_HEARING: Positional = Positional {
+    min_distance: 32.0,
+    max_distance: 450.0,
+    rolloff: 2.0,
+    pan_distance: 500.0,
+};
+
+/// Kick up dust this far below the entity's origin, in world units.
+const FOOTSTEP_DUST_OFFSET: Vec3 = Vec3::new(0.0, -16.0, 0.0);
+
+/// Play a step sound and kick up some dust.
+fn play_footstep_sound_effect(
+    trigger: Trigger<FrameEvent>,
+    mut commands: Commands,
+    footstep_assets: Option<Res<FootstepAssets>>,
+    walker_query: Query<&GlobalTransform, Without<Airborne>>,
+) {
+    if trigger.name != FOOTSTEP_EVENT {
+        return;
+    }
+    let Ok(transform) = walker_query.get(trigger.target()) else {
+        return;
+    };
+    let Some(footstep_assets) = footstep_assets else {
+        return;
+    };
+
+    commands.spawn((
+        sound_effect_from_bank(footstep_assets.steps.clone()),
+        SoundPosition::On(trigger.target()),
+        FOOTSTEP_HEARING,
+    ));
+    commands.spawn((
+        particle_emitter(footstep_assets.dust.clone()),
+        Transform::from_translation(transform.translation() + FOOTSTEP_DUST_OFFSET),
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SoundEffect;
+
+    /// How many step sounds one footstep event plays for a walker.
+    fn steps_played(airborne: bool) -> usize {
+        let mut world = World::new();
+        world.init_resource::<FootstepAssets>();
+        world.add_observer(play_footstep_sound_effect);
+        let walker = world.spawn(GlobalTransform::default()).id();
+        if airborne {
+            world.entity_mut(walker).insert(Airborne);
+        }
+        world.trigger_targets(
+            FrameEvent {
+                name: FOOTSTEP_EVENT.to_string(),
+            },
+            walker,
+        );
+        world.flush();
+        world
+            .query_filtered::<(), With<SoundEffect>>()
+            .iter(&world)
+            .count()
+    }
+
+    #[test]
+    fn plays_steps_on_the_ground() {
+        assert_eq!(steps_played(false), 1);
+    }
+
+    #[test]
+    fn skips_steps_while_airborne() {
+        assert_eq!(steps_played(true), 0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Health, damage and death.
+//!
+//! Every source of harm (projectiles, enemy contact, hazard tiles) sends a
+//! [`DamageEvent`]. [`apply_damage`] is the only place that decides what a hit
+//! actually does, so game rules such as invulnerability frames and knockback
+//! live in one place.
+
+use bevy::{
+    ecs::entity_disabling::Disabled,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{SoundBank, SoundPosition, sound_effect_from_bank},
+    demo::{
+        camera::AddTrauma, hit_stop::HitStop, movement::Knockback, player::Player,
+        respawn::ResetOnRespawn,
+    },
+    particles::{ParticleEffect, particle_emitter},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Health>();
+    app.register_type::<Invulnerable>();
+    app.register_type::<Team>();
+    app.register_type::<Hurtbox>();
+    app.register_type::<ContactDamage>();
+    app.add_event::<DamageEvent>();
+
+    app.register_type::<HealthAssets>();
+    app.load_resource::<HealthAssets>("manifests/health.assets.ron");
+
+    app.add_systems(
+        Update,
+        (
+            tick_invulnerability.in_set(AppSystems::TickTimers),
+            (apply_contact_damage, apply_damage, flash_invulnerable)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_observer(despawn_on_death);
+}
+
+/// Hit points of a damageable entity.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+    /// How long the entity ignores further damage after being hit, in seconds.
+    pub invulnerability_secs: f32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self {
+            current: max,
+            max,
+            invulnerability_secs: 0.0,
+        }
+    }
+
+    pub fn with_invulnerability(mut self, secs: f32) -> Self {
+        self.invulnerability_secs = secs;
+        self
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    /// Restore health to its maximum.
+    pub fn heal_full(&mut self) {
+        self.current = self.max;
+    }
+}
+
+/// A request to damage an entity. Send this instead of touching [`Health`] directly.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    /// The entity that dealt the damage, if any.
+    pub source: Option<Entity>,
+    pub amount: u32,
+    /// Velocity added to the target's [`Knockback`], in world units per second.
+    pub knockback: Vec2,
+    /// Whether the damage ignores [`Invulnerable`] (e.g. falling out of the world).
+    pub piercing: bool,
+}
+
+impl DamageEvent {
+    pub fn new(target: Entity, amount: u32) -> Self {
+        Self {
+            target,
+            source: None,
+            amount,
+            knockback: Vec2::ZERO,
+            piercing: false,
+        }
+    }
+
+    /// Damage that kills outright, ignoring invulnerability.
+    pub fn lethal(target: Entity) -> Self {
+        Self {
+            amount: u32::MAX,
+            piercing: true,
+            ..Self::new(target, 0)
+        }
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_knockback(mut self, knockback: Vec2) -> Self {
+        self.knockback = knockback;
+        self
+    }
+}
+
+/// Triggered on an entity when its [`Health`] reaches zero.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Died;
+
+/// Temporarily ignores non-piercing damage. The entity's sprite flashes while
+/// this is present.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Invulnerable(pub Timer);
+
+impl Invulnerable {
+    pub fn from_seconds(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// Which side an entity is on. [`ContactDamage`] never hurts its own team.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum Team {
+    Player,
+    Enemy,
+    /// Hazards that hurt everyone, such as spikes.
+    Environment,
+}
+
+/// The area in which an entity can be hurt, in world units (before scaling).
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Hurtbox {
+    pub size: Vec2,
+}
+
+/// Deals damage to any [`Hurtbox`] of another [`Team`] that it overlaps.
+/// Projectiles, enemy bodies and hazard tiles all use this.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct ContactDamage {
+    pub amount: u32,
+    /// Knockback speed applied away from the damage source.
+    pub knockback: f32,
+    /// The damaging area, in world units (before scaling).
+    pub size: Vec2,
+    /// Despawn after the first hit (e.g. projectiles).
+    pub despawn_on_hit: bool,
+}
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct HealthAssets {
+    #[dependency]
+    hit: Handle<SoundBank>,
+    #[dependency]
+    hit_particles: Handle<ParticleEffect>,
+    #[dependency]
+    death_particles: Handle<ParticleEffect>,
+}
+
+fn world_aabb(transform: &GlobalTransform, size: Vec2) -> Aabb2d {
+    let scale = transform.compute_transform().scale.truncate().abs();
+    Aabb2d::new(transform.translation().truncate(), size * scale / 2.0)
+}
+
+fn apply_contact_damage(
+    mut commands: Commands,
+    attacker_query: Query<(Entity, &ContactDamage, &Team, &GlobalTransform)>,
+    target_query: Query<(Entity, &Hurtbox, &Team, &GlobalTransform)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (attacker, contact, attacker_team, attacker_transform) in &attacker_query {
+        let attacker_box = world_aabb(attacker_transform, contact.size);
+        for (target, hurtbox, target_team, target_transform) in &target_query {
+            if attacker == target || attacker_team == target_team {
+                continue;
+            }
+            let target_box = world_aabb(target_transform, hurtbox.size);
+            if !attacker_box.intersects(&target_box) {
+                continue;
+            }
+
+            let direction = (target_transform.translation() - attacker_transform.translation())
+                .truncate()
+                .normalize_or(Vec2::Y);
+            damage_events.write(
+                DamageEvent::new(target, contact.amount)
+                    .with_source(attacker)
+                    .with_knockback(direction * contact.knockback),
+            );
+
+            if contact.despawn_on_hit {
+                commands.entity(attacker).despawn();
+                break;
+            }
+        }
+    }
+}
+
+const HIT_TRAUMA: f32 = 0.3;
+const HIT_STOP_SECS: f32 = 0.05;
+const DEATH_TRAUMA: f32 = 0.6;
+const DEATH_HIT_STOP_SECS: f32 = 0.12;
+
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut target_query: Query<(
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&mut Knockback>,
+        &GlobalTransform,
+    )>,
+    health_assets: Option<Res<HealthAssets>>,
+) {
+    for event in damage_events.read() {
+        let Ok((mut health, invulnerable, knockback, transform)) =
+            target_query.get_mut(event.target)
+        else {
+            continue;
+        };
+        if health.is_dead() || (invulnerable.is_some() && !event.piercing) {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(event.amount);
+        if let Some(mut knockback) = knockback {
+            knockback.0 += event.knockback;
+        }
+        if let Some(health_assets) = &health_assets {
+            commands.spawn((
+                sound_effect_from_bank(health_assets.hit.clone()),
+                SoundPosition::At(transform.translation().truncate()),
+            ));
+            commands.spawn((
+                particle_emitter(health_assets.hit_particles.clone()),
+                Transform::from_translation(transform.translation()),
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+
+        if health.is_dead() {
+            commands.trigger(AddTrauma(DEATH_TRAUMA));
+            commands.trigger(HitStop::from_secs(DEATH_HIT_STOP_SECS));
+            commands.trigger_targets(Died, event.target);
+        } else {
+            commands.trigger(AddTrauma(HIT_TRAUMA));
+            commands.trigger(HitStop::from_secs(HIT_STOP_SECS));
+            if health.invulnerability_secs > 0.0 {
+                commands
+                    .entity(event.target)
+                    .insert(Invulnerable::from_seconds(health.invulnerability_secs));
+            }
+        }
+    }
+}
+
+fn tick_invulnerability(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut invulnerable_query: Query<(Entity, &mut Invulnerable, Option<&mut Sprite>)>,
+) {
+    for (entity, mut invulnerable, sprite) in &mut invulnerable_query {
+        invulnerable.0.tick(time.delta());
+        if invulnerable.0.finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+            if let Some(mut sprite) = sprite {
+                sprite.color.set_alpha(1.0);
+            }
+        }
+    }
+}
+
+/// How many times per second an [`Invulnerable`] sprite blinks.
+const FLASH_FREQUENCY: f32 = 10.0;
+
+fn flash_invulnerable(mut sprite_query: Query<(&Invulnerable, &mut Sprite)>) {
+    for (invulnerable, mut sprite) in &mut sprite_query {
+        let blink = (invulnerable.0.elapsed_secs() * FLASH_FREQUENCY) as u32;
+        sprite
+            .color
+            .set_alpha(if blink.is_multiple_of(2) { 0.25 } else { 1.0 });
+    }
+}
+
+/// Anything that isn't the player simply disappears when it dies, or is put
+/// away until the player respawns if it's [`ResetOnRespawn::Restore`]. Player
+/// death is handled in [`crate::demo::respawn`].
+fn despawn_on_death(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    health_assets: Option<Res<HealthAssets>>,
+    transform_query: Query<(&GlobalTransform, Option<&ResetOnRespawn>), Without<Player>>,
+) {
+    let entity = trigger.target();
+    let Ok((transform, reset)) = transform_query.get(entity) else {
+        return;
+    };
+    if reset == Some(&ResetOnRespawn::Restore) {
+        commands
+            .entity(entity)
+            .insert((Disabled, Visibility::Hidden));
+    } else {
+        commands.entity(entity).despawn();
+    }
+    if let Some(health_assets) = health_assets {
+        commands.spawn((
+            particle_emitter(health_assets.death_particles.clone()),
+            Transform::from_translation(transform.translation()),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
@@ -21,14 +21,17 @@ use bevy::{
     asset::{AssetLoader, AssetPath, io::Reader},
     platform::collections::HashMap,
     prelude::{
-        Added, Asset, AssetApp, AssetEvent, AssetId, Assets, Bundle, Commands, Component, Entity,
-        EventReader, GlobalTransform, Handle, Image, Plugin, Query, Res, Transform, Update,
+        Added, Asset, AssetApp, AssetEvent, AssetId, Assets, AudioSource, Bundle, ChildOf,
+        Commands, Component, Entity, EventReader, GlobalTransform, Handle, Image, Name, Plugin,
+        Query, Rect, Res, Transform, Update, Vec2,
     },
     reflect::TypePath,
 };
 use bevy_ecs_tilemap::prelude::*;
 use thiserror::Error;
 
+use crate::demo::health::{ContactDamage, Team};
+
 #[derive(Default)]
 pub struct TiledMapPlugin;
 
@@ -48,6 +51,107 @@ pub struct TiledMap {
 
     // The offset into the tileset_images for each tile id within each tileset.
     pub tile_image_offsets: HashMap<(usize, tiled::TileId), u32>,
+
+    /// Assets the map asks for in its properties, loaded along with the map.
+    pub dependencies: MapDependencies,
+}
+
+/// Assets a map declares in its custom properties. Each property is a
+/// comma-separated list of paths relative to the `assets` folder:
+///
+/// - `music`: the soundtrack, played in a random order.
+/// - `backgrounds`: images drawn behind the map, from the back to the front.
+/// - `ambience`: sounds looped for as long as the map is around.
+/// - `sprite_sheets`: any other images the map's contents need.
+#[derive(Default, Debug, Clone)]
+pub struct MapDependencies {
+    pub music: Vec<Handle<AudioSource>>,
+    pub backgrounds: Vec<Handle<Image>>,
+    pub ambience: Vec<Handle<AudioSource>>,
+    /// Held so the images stay loaded for as long as the map is.
+    _sprite_sheets: Vec<Handle<Image>>,
+}
+
+impl MapDependencies {
+    fn load(map: &tiled::Map, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            music: load_paths(map, "music", load_context),
+            backgrounds: load_paths(map, "backgrounds", load_context),
+            ambience: load_paths(map, "ambience", load_context),
+            _sprite_sheets: load_paths(map, "sprite_sheets", load_context),
+        }
+    }
+}
+
+/// Load each path listed in the map property `name`.
+fn load_paths<A: Asset>(
+    map: &tiled::Map,
+    name: &str,
+    load_context: &mut bevy::asset::LoadContext,
+) -> Vec<Handle<A>> {
+    let Some(tiled::PropertyValue::StringValue(paths)) = map.properties.get(name) else {
+        return Vec::new();
+    };
+    paths
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(|path| load_context.load(path.to_string()))
+        .collect()
+}
+
+impl TiledMap {
+    /// The map's extent in world units, relative to the map entity.
+    /// Maps are spawned centered on their entity.
+    pub fn world_rect(&self) -> Rect {
+        Rect::from_center_size(Vec2::ZERO, self.pixel_size())
+    }
+
+    /// Convert a point in Tiled's pixel coordinates (origin at the top-left,
+    /// y pointing down) to world units relative to the map entity.
+    pub fn to_world(&self, x: f32, y: f32) -> Vec2 {
+        let half_size = self.pixel_size() / 2.0;
+        Vec2::new(x - half_size.x, half_size.y - y)
+    }
+
+    /// All objects with the given class, from every object layer.
+    pub fn objects<'a>(&'a self, class: &'a str) -> impl Iterator<Item = tiled::Object<'a>> + 'a {
+        self.map
+            .layers()
+            .filter_map(|layer| match layer.layer_type() {
+                tiled::LayerType::Objects(object_layer) => Some(object_layer),
+                _ => None,
+            })
+            .flat_map(|object_layer| object_layer.objects())
+            .filter(move |object| object.user_type == class)
+    }
+
+    /// The world-space area covered by a rectangle object, relative to the map entity.
+    pub fn object_rect(&self, object: &tiled::Object) -> Option<Rect> {
+        let tiled::ObjectShape::Rect { width, height } = object.shape else {
+            return None;
+        };
+        let top_left = self.to_world(object.x, object.y);
+        Some(Rect::from_corners(
+            top_left,
+            top_left + Vec2::new(width, -height),
+        ))
+    }
+
+    /// A string property set on the map itself.
+    pub fn string_property(&self, name: &str) -> Option<&str> {
+        match self.map.properties.get(name) {
+            Some(tiled::PropertyValue::StringValue(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn pixel_size(&self) -> Vec2 {
+        Vec2::new(
+            (self.map.width * self.map.tile_width) as f32,
+            (self.map.height * self.map.tile_height) as f32,
+        )
+    }
 }
 
 // Stores a list of tiled layers.
@@ -179,10 +283,12 @@ impl AssetLoader for TiledLoader {
             tilemap_textures.insert(tileset_index, tilemap_texture);
         }
 
+        let dependencies = MapDependencies::load(&map, load_context);
         let asset_map = TiledMap {
             map,
             tilemap_textures,
             tile_image_offsets,
+            dependencies,
         };
 
         info!("Loaded map: {}", load_context.path().display());
@@ -195,12 +301,15 @@ impl AssetLoader for TiledLoader {
     }
 }
 
+/// Knockback speed applied by tiles with a `damage` property.
+const HAZARD_KNOCKBACK: f32 = 600.0;
+
 pub fn process_loaded_maps(
     mut commands: Commands,
     mut map_events: EventReader<AssetEvent<TiledMap>>,
     maps: Res<Assets<TiledMap>>,
-    tile_storage_query: Query<(Entity, &TileStorage)>,
     mut map_query: Query<(
+        Entity,
         &TiledMapHandle,
         &mut TiledLayersStorage,
         &TilemapRenderSettings,
@@ -234,20 +343,15 @@ pub fn process_loaded_maps(
     }
 
     for changed_map in changed_maps.iter() {
-        for (map_handle, mut layer_storage, render_settings) in map_query.iter_mut() {
+        for (map_entity, map_handle, mut layer_storage, render_settings) in map_query.iter_mut() {
             // only deal with currently changed map
             if map_handle.0.id() != *changed_map {
                 continue;
             }
             if let Some(tiled_map) = maps.get(&map_handle.0) {
-                // TODO: Create a RemoveMap component..
-                for layer_entity in layer_storage.storage.values() {
-                    if let Ok((_, layer_tile_storage)) = tile_storage_query.get(*layer_entity) {
-                        for tile in layer_tile_storage.iter().flatten() {
-                            commands.entity(*tile).despawn()
-                        }
-                    }
-                    // commands.entity(*layer_entity).despawn_recursive();
+                // Despawning a layer takes its tiles and hazards along.
+                for (_, layer_entity) in layer_storage.storage.drain() {
+                    commands.entity(layer_entity).despawn();
                 }
 
                 // The TilemapBundle requires that all tile images come exclusively from a single
@@ -317,7 +421,8 @@ pub fn process_loaded_maps(
                         };
 
                         let mut tile_storage = TileStorage::empty(map_size);
-                        let layer_entity = commands.spawn_empty().id();
+                        // Layers belong to the map, so despawning the map takes them along.
+                        let layer_entity = commands.spawn(ChildOf(map_entity)).id();
 
                         for x in 0..map_size.x {
                             for y in 0..map_size.y {
@@ -365,8 +470,36 @@ pub fn process_loaded_maps(
                                         },
                                         ..Default::default()
                                     })
+                                    .insert(ChildOf(layer_entity))
                                     .id();
                                 tile_storage.set(&tile_pos, tile_entity);
+
+                                // Tiles with a `damage` property hurt anything that touches them.
+                                let damage = layer_tile.get_tile().and_then(|tile| {
+                                    match tile.properties.get("damage") {
+                                        Some(tiled::PropertyValue::IntValue(damage)) => {
+                                            Some(*damage)
+                                        }
+                                        _ => None,
+                                    }
+                                });
+                                if let Some(damage) = damage {
+                                    let center = Vec2::new(
+                                        (x as f32 + 0.5 - map_size.x as f32 / 2.0) * grid_size.x,
+                                        (y as f32 + 0.5 - map_size.y as f32 / 2.0) * grid_size.y,
+                                    );
+                                    commands.entity(layer_entity).with_child((
+                                        Name::new("Hazard Tile"),
+                                        ContactDamage {
+                                            amount: damage.max(0) as u32,
+                                            knockback: HAZARD_KNOCKBACK,
+                                            size: Vec2::new(grid_size.x, grid_size.y),
+                                            despawn_on_hit: false,
+                                        },
+                                        Team::Environment,
+                                        Transform::from_translation(center.extend(0.0)),
+                                    ));
+                                }
                             }
                         }
 
@@ -0,0 +1,70 @@
+//! Hit-stop: briefly slow the game down on impactful events so they land.
+//!
+//! Trigger a [`HitStop`] from anywhere with `commands.trigger(HitStop::from_secs(0.05))`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, accessibility::Accessibility, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<HitStopTimer>();
+    app.init_resource::<HitStopTimer>();
+    app.add_observer(start_hit_stop);
+    app.add_systems(Update, tick_hit_stop.in_set(AppSystems::TickTimers));
+    app.add_systems(OnExit(Screen::Gameplay), end_hit_stop);
+}
+
+/// How fast virtual time runs during a hit-stop.
+const HIT_STOP_SPEED: f32 = 0.05;
+
+/// Slow down virtual time, and with it everything in `PausableSystems`, for
+/// the given real-time duration. Overlapping hit-stops don't stack; the longer
+/// one wins.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HitStop(pub Duration);
+
+impl HitStop {
+    pub fn from_secs(secs: f32) -> Self {
+        Self(Duration::from_secs_f32(secs))
+    }
+}
+
+/// Real time left in the current hit-stop.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct HitStopTimer(Duration);
+
+fn start_hit_stop(
+    trigger: Trigger<HitStop>,
+    accessibility: Res<Accessibility>,
+    mut timer: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if accessibility.reduced_motion {
+        return;
+    }
+    timer.0 = timer.0.max(trigger.event().0);
+    time.set_relative_speed(HIT_STOP_SPEED);
+}
+
+fn tick_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut timer: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if timer.0.is_zero() {
+        return;
+    }
+    // Virtual time is what's being slowed down, so it can't be used to measure the stop.
+    timer.0 = timer.0.saturating_sub(real_time.delta());
+    if timer.0.is_zero() {
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn end_hit_stop(mut timer: ResMut<HitStopTimer>, mut time: ResMut<Time<Virtual>>) {
+    timer.0 = Duration::ZERO;
+    time.set_relative_speed(1.0);
+}
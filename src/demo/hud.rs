@@ -0,0 +1,286 @@
+//! The in-game HUD: the player's health and lives, their weapon, the items
+//! they've collected and optionally how long the level has taken.
+//!
+//! Every part of the HUD updates when the value it shows changes, rather than
+//! every frame. It's laid out in [`Px`], so it follows the UI scale setting,
+//! and it sits below the pause overlay and respawn fade.
+
+use bevy::{prelude::*, ui::Val::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::{
+        health::Health,
+        player::Player,
+        progression::CollectedItems,
+        respawn::Lives,
+        stats::{LevelStats, format_time},
+        weapon::Weapon,
+    },
+    screens::Screen,
+    theme::{palette::*, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<HudSettings>();
+    app.init_resource::<HudSettings>();
+    app.register_type::<HealthBar>();
+    app.register_type::<LivesLabel>();
+    app.register_type::<WeaponDisplay>();
+    app.register_type::<AmmoLabel>();
+    app.register_type::<CooldownBar>();
+    app.register_type::<CollectedLabel>();
+    app.register_type::<LevelTimerLabel>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
+    app.add_systems(
+        Update,
+        (
+            update_health_bar,
+            update_lives_label,
+            update_weapon_display,
+            update_collected_label.run_if(resource_changed::<CollectedItems>),
+            show_level_timer.run_if(resource_changed::<HudSettings>),
+            update_level_timer.run_if(timer_shown),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Player preferences for the HUD. Saved with the other settings.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct HudSettings {
+    /// Show how long the current level has taken.
+    pub show_timer: bool,
+}
+
+/// Holds one segment per point of the player's maximum health.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HealthBar;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LivesLabel;
+
+/// Shows the player's [`Weapon`], if they have one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct WeaponDisplay;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct AmmoLabel;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CooldownBar;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CollectedLabel;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LevelTimerLabel;
+
+const HEALTH_SEGMENT_SIZE: Vec2 = Vec2::new(18.0, 24.0);
+
+fn spawn_hud(
+    mut commands: Commands,
+    collected: Res<CollectedItems>,
+    hud_settings: Res<HudSettings>,
+) {
+    commands
+        .spawn((
+            Name::new("HUD"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Percent(100.0),
+                padding: UiRect::all(Px(16.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+            // Below the pause overlay and respawn fade.
+            GlobalZIndex(0),
+            Pickable::IGNORE,
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Name::new("Player Status"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Px(8.0),
+                    ..default()
+                },
+                children![
+                    (
+                        Name::new("Health Bar"),
+                        Node {
+                            column_gap: Px(4.0),
+                            ..default()
+                        },
+                        HealthBar,
+                    ),
+                    (widget::label(""), LivesLabel),
+                ],
+            ));
+            parent
+                .spawn((
+                    Name::new("Level Status"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::FlexEnd,
+                        row_gap: Px(8.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((widget::label(collected_text(&collected)), CollectedLabel));
+                    parent.spawn((
+                        widget::label(format_time(default())),
+                        LevelTimerLabel,
+                        timer_visibility(&hud_settings),
+                    ));
+                    parent
+                        .spawn((
+                            Name::new("Weapon"),
+                            Node {
+                                display: Display::None,
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::FlexEnd,
+                                row_gap: Px(4.0),
+                                ..default()
+                            },
+                            WeaponDisplay,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((widget::label(""), AmmoLabel));
+                            parent.spawn(widget::progress_bar()).insert((
+                                Node {
+                                    width: Px(120.0),
+                                    height: Px(12.0),
+                                    border: UiRect::all(Px(2.0)),
+                                    ..default()
+                                },
+                                CooldownBar,
+                            ));
+                        });
+                });
+        });
+}
+
+fn update_health_bar(
+    mut commands: Commands,
+    player_query: Query<&Health, (With<Player>, Changed<Health>)>,
+    bar: Single<(Entity, Option<&Children>), With<HealthBar>>,
+    mut segment_query: Query<&mut BackgroundColor>,
+) {
+    let Ok(health) = player_query.single() else {
+        return;
+    };
+    let (bar, segments) = *bar;
+    let segments = segments.map_or(&[][..], |children| &children[..]);
+    // Rebuild the bar if the player's maximum health changed.
+    if segments.len() != health.max as usize {
+        for &segment in segments {
+            commands.entity(segment).despawn();
+        }
+        for index in 0..health.max {
+            commands.spawn((
+                Name::new("Health Segment"),
+                Node {
+                    width: Px(HEALTH_SEGMENT_SIZE.x),
+                    height: Px(HEALTH_SEGMENT_SIZE.y),
+                    ..default()
+                },
+                BackgroundColor(segment_color(index, health)),
+                ChildOf(bar),
+            ));
+        }
+        return;
+    }
+    for (index, &segment) in (0..).zip(segments) {
+        if let Ok(mut background) = segment_query.get_mut(segment) {
+            background.0 = segment_color(index, health);
+        }
+    }
+}
+
+fn segment_color(index: u32, health: &Health) -> Color {
+    if index < health.current {
+        HEALTH_FULL
+    } else {
+        HEALTH_EMPTY
+    }
+}
+
+fn update_lives_label(
+    lives_query: Query<&Lives, (With<Player>, Changed<Lives>)>,
+    mut label: Single<&mut Text, With<LivesLabel>>,
+) {
+    if let Ok(lives) = lives_query.single() {
+        label.0 = format!("Lives: {}", lives.0);
+    }
+}
+
+fn update_weapon_display(
+    weapon_query: Query<&Weapon, (With<Player>, Changed<Weapon>)>,
+    mut display: Single<&mut Node, With<WeaponDisplay>>,
+    mut ammo_label: Single<&mut Text, With<AmmoLabel>>,
+    mut cooldown_bar: Single<&mut ProgressBar, With<CooldownBar>>,
+) {
+    let Ok(weapon) = weapon_query.single() else {
+        return;
+    };
+    display.display = Display::Flex;
+    ammo_label.0 = format!("Ammo: {}/{}", weapon.ammo, weapon.max_ammo);
+    cooldown_bar.0 = weapon.readiness();
+}
+
+fn collected_text(collected: &CollectedItems) -> String {
+    format!("Items: {}", collected.0.len())
+}
+
+fn update_collected_label(
+    collected: Res<CollectedItems>,
+    mut label: Single<&mut Text, With<CollectedLabel>>,
+) {
+    label.0 = collected_text(&collected);
+}
+
+fn timer_visibility(hud_settings: &HudSettings) -> Visibility {
+    if hud_settings.show_timer {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+fn timer_shown(hud_settings: Res<HudSettings>) -> bool {
+    hud_settings.show_timer
+}
+
+fn show_level_timer(
+    hud_settings: Res<HudSettings>,
+    mut visibility: Single<&mut Visibility, With<LevelTimerLabel>>,
+) {
+    **visibility = timer_visibility(&hud_settings);
+}
+
+/// Only touches the label when the shown time changes, once a second.
+fn update_level_timer(
+    stats: Res<LevelStats>,
+    mut shown_secs: Local<u64>,
+    mut label: Single<&mut Text, With<LevelTimerLabel>>,
+) {
+    let secs = stats.time.as_secs();
+    if *shown_secs != secs {
+        *shown_secs = secs;
+        label.0 = format_time(stats.time);
+    }
+}
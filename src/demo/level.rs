@@ -1,65 +1,224 @@
-//! Spawn the main level.
+//! Spawn the current level.
+//!
+//! The level's map declares its own music, backgrounds, ambience and sprite
+//! sheets in its properties (see [`MapDependencies`]). They load along with the
+//! map, so the loading screen waits for exactly what the level needs. Which
+//! level that is, and where in it the player starts, comes from the
+//! [`LevelProgression`].
 
 use bevy::prelude::*;
 
 use crate::{
-    asset_tracking::LoadResource,
-    audio::music,
-    demo::helpers,
-    demo::player::{PlayerAssets, setup_player},
+    asset_tracking::RegisterManifestAsset,
+    audio::{MusicDirector, Playlist, sound_effect},
+    demo::{
+        checkpoint::{ActiveCheckpoint, CHECKPOINT_CLASS, CheckpointAssets, checkpoint},
+        collectible::{COLLECTIBLE_CLASS, CollectibleAssets, collectible, collectible_id},
+        enemy::{ENEMY_CLASS, EnemyAssets, enemy},
+        helpers::tiled::{MapDependencies, TiledMap, TiledMapBundle, TiledMapHandle},
+        player::{PlayerAssets, RespawnPoint, setup_player},
+        progression::{
+            CollectedItems, EXIT_CLASS, LevelExit, LevelProgression, LevelRegistry, SPAWN_CLASS,
+        },
+    },
     screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_manifest_asset::<TiledMap>();
     app.register_type::<LevelAssets>();
-    app.load_resource::<LevelAssets>();
+    app.add_systems(OnExit(Screen::Gameplay), stop_level_music);
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, Default)]
 #[reflect(Resource)]
 pub struct LevelAssets {
+    /// The level's map, along with everything its properties ask for.
     #[dependency]
-    music: Handle<AudioSource>,
+    map: Handle<TiledMap>,
 }
 
-impl FromWorld for LevelAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/music/Fluffing A Duck.ogg"),
-        }
-    }
+/// Backgrounds are drawn this far behind the map's first layer, one unit apart.
+const BACKGROUND_Z: f32 = -10.0;
+
+fn stop_level_music(mut music_director: ResMut<MusicDirector>) {
+    music_director.stop();
 }
 
-/// A system that spawns the main level.
+/// A system that spawns the current level.
 pub fn spawn_level(
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
     player_assets: Res<PlayerAssets>,
-    texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    asset_server: Res<AssetServer>,
+    checkpoint_assets: Res<CheckpointAssets>,
+    enemy_assets: Res<EnemyAssets>,
+    collectible_assets: Res<CollectibleAssets>,
+    collected: Res<CollectedItems>,
+    maps: Res<Assets<TiledMap>>,
+    progression: Res<LevelProgression>,
+    registry: Res<LevelRegistry>,
+    mut respawn_point: ResMut<RespawnPoint>,
+    mut music_director: ResMut<MusicDirector>,
 ) {
-    info!("Spawning level and music");
+    info!("Spawning level `{}` and music", progression.current);
 
-    // Check if music asset is loaded
-    info!("Music handle: {:?}", level_assets.music);
+    let tiled_map = maps.get(&level_assets.map);
+    let start = tiled_map.map_or(Vec2::ZERO, |tiled_map| {
+        spawn_point(tiled_map, progression.entry.as_deref())
+    });
+    respawn_point.0 = start;
     commands.spawn((
         Name::new("Level"),
         Transform::default(),
         Visibility::default(),
         StateScoped(Screen::Gameplay),
-        children![
-            setup_player(player_assets, texture_atlas_layouts),
-            (
-                Name::new("Gameplay Music"),
-                music(level_assets.music.clone())
-            )
-        ],
+        children![setup_player(player_assets, start)],
     ));
-    let map_handle = helpers::tiled::TiledMapHandle(asset_server.load("maps/map1.tmx"));
 
-    commands.spawn(helpers::tiled::TiledMapBundle {
-        tiled_map: map_handle,
-        ..Default::default()
+    let map_entity = commands
+        .spawn((
+            TiledMapBundle {
+                tiled_map: TiledMapHandle(level_assets.map.clone()),
+                ..Default::default()
+            },
+            Visibility::default(),
+            StateScoped(Screen::Gameplay),
+        ))
+        .id();
+
+    let Some(tiled_map) = tiled_map else {
+        music_director.stop();
+        return;
+    };
+    let MapDependencies {
+        music,
+        backgrounds,
+        ambience,
+        ..
+    } = &tiled_map.dependencies;
+    if music.is_empty() {
+        music_director.stop();
+    } else {
+        music_director.play_playlist(Playlist {
+            tracks: music.clone(),
+            shuffle: true,
+        });
+    }
+
+    let map_size = tiled_map.world_rect().size();
+    for (index, background) in backgrounds.iter().enumerate() {
+        commands.entity(map_entity).with_child((
+            Name::new("Background"),
+            Sprite {
+                image: background.clone(),
+                custom_size: Some(map_size),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, BACKGROUND_Z + index as f32),
+        ));
+    }
+    for sound in ambience {
+        commands
+            .spawn((
+                Name::new("Ambience"),
+                sound_effect(sound.clone()),
+                ChildOf(map_entity),
+            ))
+            .insert(PlaybackSettings::LOOP);
+    }
+
+    for object in tiled_map.objects(EXIT_CLASS) {
+        let Some(rect) = tiled_map.object_rect(&object) else {
+            warn!("Level exit `{}` isn't a rectangle", object.name);
+            continue;
+        };
+        let property = |name: &str| match object.properties.get(name) {
+            Some(tiled::PropertyValue::StringValue(value)) => Some(value.clone()),
+            _ => None,
+        };
+        let Some(level) = property("level").filter(|level| registry.get(level).is_some()) else {
+            warn!("Level exit `{}` doesn't lead to a known level", object.name);
+            continue;
+        };
+        commands.entity(map_entity).with_child((
+            Name::new("Level Exit"),
+            LevelExit {
+                level,
+                spawn: property("spawn"),
+                size: rect.size(),
+            },
+            Transform::from_translation(rect.center().extend(0.0)),
+        ));
+    }
+
+    for object in tiled_map.objects(CHECKPOINT_CLASS) {
+        let Some(rect) = tiled_map.object_rect(&object) else {
+            warn!("Checkpoint `{}` isn't a rectangle", object.name);
+            continue;
+        };
+        let mut entity = commands.spawn((
+            checkpoint(&checkpoint_assets, object.name.clone(), rect),
+            ChildOf(map_entity),
+        ));
+        // Entering the level at a checkpoint means it was the last one touched.
+        if progression.entry.as_deref() == Some(object.name.as_str()) {
+            entity.insert(ActiveCheckpoint);
+        }
+    }
+
+    for object in tiled_map.objects(COLLECTIBLE_CLASS) {
+        let id = collectible_id(&progression.current, object.id());
+        if collected.0.contains(&id) {
+            continue;
+        }
+        let position = tiled_map.to_world(object.x, object.y);
+        commands
+            .entity(map_entity)
+            .with_child(collectible(&collectible_assets, id, position));
+    }
+
+    for object in tiled_map.objects(ENEMY_CLASS) {
+        let position = tiled_map.to_world(object.x, object.y);
+        commands
+            .entity(map_entity)
+            .with_child(enemy(&enemy_assets, position));
+    }
+}
+
+/// Where to put the player when entering the level at the spawn point or
+/// checkpoint named `entry`, or at the level's start.
+fn spawn_point(tiled_map: &TiledMap, entry: Option<&str>) -> Vec2 {
+    let point = entry.and_then(|entry| {
+        let point = named_point(tiled_map, entry);
+        if point.is_none() {
+            warn!("No spawn point named `{entry}`, starting at the beginning");
+        }
+        point
     });
+    point.unwrap_or_else(|| {
+        start_point(tiled_map).map_or(Vec2::ZERO, |object| tiled_map.to_world(object.x, object.y))
+    })
+}
+
+/// The spawn point or the center of the checkpoint named `name`.
+fn named_point(tiled_map: &TiledMap, name: &str) -> Option<Vec2> {
+    let spawn = tiled_map
+        .objects(SPAWN_CLASS)
+        .find(|object| object.name == name)
+        .map(|object| tiled_map.to_world(object.x, object.y));
+    spawn.or_else(|| {
+        tiled_map
+            .objects(CHECKPOINT_CLASS)
+            .find(|object| object.name == name)
+            .and_then(|object| tiled_map.object_rect(&object))
+            .map(|rect| rect.center())
+    })
+}
+
+/// The spawn point named `start`, or else the first one.
+fn start_point(tiled_map: &TiledMap) -> Option<tiled::Object<'_>> {
+    tiled_map
+        .objects(SPAWN_CLASS)
+        .find(|object| object.name == "start")
+        .or_else(|| tiled_map.objects(SPAWN_CLASS).next())
 }
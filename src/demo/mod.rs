@@ -7,17 +7,45 @@ use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 
 mod animation;
+mod bounds;
+mod camera;
+mod checkpoint;
+mod collectible;
+mod enemy;
+mod footsteps;
+pub mod health;
 pub mod helpers;
+mod hit_stop;
+pub mod hud;
 pub mod level;
 mod movement;
 pub mod player;
+pub mod progression;
+pub mod respawn;
+pub mod stats;
+mod weapon;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
+        bounds::plugin,
+        camera::plugin,
+        checkpoint::plugin,
+        collectible::plugin,
+        enemy::plugin,
+        footsteps::plugin,
+        health::plugin,
+        hit_stop::plugin,
+        hud::plugin,
         level::plugin,
+    ));
+    app.add_plugins((
         movement::plugin,
         player::plugin,
+        progression::plugin,
+        respawn::plugin,
+        stats::plugin,
+        weapon::plugin,
     ));
     app.add_plugins(TilemapPlugin);
     app.add_plugins(helpers::tiled::TiledMapPlugin);
@@ -1,11 +1,12 @@
 //! Minimal movement system for player debugging.
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<MovementController>();
-    app.register_type::<ScreenWrap>();
-    app.add_systems(Update, (apply_movement, apply_screen_wrap));
+    app.register_type::<Knockback>();
+    app.register_type::<Airborne>();
+    app.add_systems(Update, (apply_movement, update_airborne).chain());
 }
 
 /// These are the movement parameters for our character controller.
@@ -13,6 +14,7 @@ pub(super) fn plugin(app: &mut App) {
 /// other players as well.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
+#[require(Knockback)]
 pub struct MovementController {
     /// The direction the character wants to move in.
     pub intent: Vec2,
@@ -32,29 +34,45 @@ impl Default for MovementController {
     }
 }
 
-fn apply_movement(
+/// Velocity from being hit, in world units per second. It decays over time and
+/// is added on top of the controller's own movement.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Knockback(pub Vec2);
+
+/// How quickly [`Knockback`] decays, per second.
+const KNOCKBACK_DAMPING: f32 = 8.0;
+
+/// Marks an entity that is off the ground, so its footsteps are silent. Strong
+/// [`Knockback`] sets this; a jump should insert and remove it too.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Airborne;
+
+/// Knockback faster than this lifts an entity off its feet, in world units per second.
+const AIRBORNE_KNOCKBACK_SPEED: f32 = 150.0;
+
+pub(super) fn apply_movement(
     time: Res<Time>,
-    mut movement_query: Query<(&MovementController, &mut Transform)>,
+    mut movement_query: Query<(&MovementController, &mut Knockback, &mut Transform)>,
 ) {
-    for (controller, mut transform) in &mut movement_query {
-        let velocity = controller.max_speed * controller.intent;
+    for (controller, mut knockback, mut transform) in &mut movement_query {
+        let velocity = controller.max_speed * controller.intent + knockback.0;
         transform.translation += velocity.extend(0.0) * time.delta_secs();
+        knockback.0 *= (-KNOCKBACK_DAMPING * time.delta_secs()).exp();
     }
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-pub struct ScreenWrap;
-
-fn apply_screen_wrap(
-    window: Single<&Window, With<PrimaryWindow>>,
-    mut wrap_query: Query<&mut Transform, With<ScreenWrap>>,
+fn update_airborne(
+    mut commands: Commands,
+    knockback_query: Query<(Entity, &Knockback, Has<Airborne>)>,
 ) {
-    let size = window.size() + 256.0;
-    let half_size = size / 2.0;
-    for mut transform in &mut wrap_query {
-        let position = transform.translation.xy();
-        let wrapped = (position + half_size).rem_euclid(size) - half_size;
-        transform.translation = wrapped.extend(transform.translation.z);
+    for (entity, knockback, airborne) in &knockback_query {
+        let thrown = knockback.0.length() > AIRBORNE_KNOCKBACK_SPEED;
+        if thrown && !airborne {
+            commands.entity(entity).insert(Airborne);
+        } else if !thrown && airborne {
+            commands.entity(entity).remove::<Airborne>();
+        }
     }
 }
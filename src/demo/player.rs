@@ -2,87 +2,89 @@
 
 use bevy::prelude::*;
 
-use crate::demo::animation::PlayerAnimation;
-use crate::demo::movement::{MovementController, ScreenWrap};
+use crate::AppSystems;
+use crate::asset_tracking::LoadResource;
+use crate::audio::AudioListener;
+use crate::demo::animation::{IDLE_CLIP, SpriteAnimation, SpriteAnimator};
+use crate::demo::bounds::StayInLevel;
+use crate::demo::camera::CameraTarget;
+use crate::demo::health::{Health, Hurtbox, Team};
+use crate::demo::movement::MovementController;
+use crate::demo::respawn::{Lives, Respawning};
+use crate::demo::weapon::Weapon;
+use crate::input::ActionState;
 
 /// Registers the player component and input system.
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Player>();
     app.register_type::<PlayerAssets>();
-    app.init_resource::<PlayerAssets>();
-    app.add_systems(Update, record_player_directional_input);
+    app.load_resource::<PlayerAssets>("manifests/player.assets.ron");
+    app.add_systems(
+        Update,
+        record_player_directional_input.in_set(AppSystems::RecordInput),
+    );
+
+    app.register_type::<RespawnPoint>();
+    app.init_resource::<RespawnPoint>();
 }
 
 /// Returns a bundle of components for spawning the player entity.
-pub fn setup_player(
-    player_assets: Res<PlayerAssets>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-) -> impl Bundle {
-    let layout = TextureAtlasLayout::from_grid(UVec2::new(14, 18), 22, 1, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let player_animation = PlayerAnimation::new();
+pub fn setup_player(player_assets: Res<PlayerAssets>, position: Vec2) -> impl Bundle {
     (
         Name::new("Player"),
         Player,
-        Sprite {
-            image: player_assets.idle.clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: texture_atlas_layout,
-                index: player_animation.get_atlas_index(),
-            }),
-            ..default()
-        },
-        Transform::from_scale(Vec2::splat(2.0).extend(1.0)),
+        CameraTarget,
+        AudioListener,
+        SpriteAnimator::new(player_assets.animation.clone(), IDLE_CLIP),
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec2::splat(2.0).extend(1.0)),
         MovementController {
             max_speed: 400.0,
             ..default()
         },
-        ScreenWrap,
-        player_animation,
+        StayInLevel,
+        Health::new(PLAYER_MAX_HEALTH).with_invulnerability(PLAYER_INVULNERABILITY_SECS),
+        Hurtbox {
+            size: Vec2::new(14.0, 18.0),
+        },
+        Team::Player,
+        Lives(PLAYER_LIVES),
+        Weapon::new(PLAYER_AMMO, PLAYER_FIRE_SECS, PLAYER_RELOAD_SECS),
     )
 }
 
+const PLAYER_MAX_HEALTH: u32 = 5;
+const PLAYER_LIVES: u32 = 3;
+const PLAYER_INVULNERABILITY_SECS: f32 = 1.0;
+const PLAYER_AMMO: u32 = 6;
+const PLAYER_FIRE_SECS: f32 = 0.25;
+const PLAYER_RELOAD_SECS: f32 = 1.2;
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Player;
 
 /// System to record player movement input and update the controller intent.
+/// The player stands still while [`Respawning`].
 fn record_player_directional_input(
-    input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut MovementController, With<Player>>,
+    action_state: Res<ActionState>,
+    mut controller_query: Query<(&mut MovementController, Has<Respawning>), With<Player>>,
 ) {
-    let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-        intent.y += 1.0;
-    }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-        intent.y -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-        intent.x -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-        intent.x += 1.0;
-    }
-    let intent = intent.normalize_or_zero();
-    for mut controller in &mut controller_query {
-        controller.intent = intent;
+    let intent = action_state.move_axis();
+    for (mut controller, respawning) in &mut controller_query {
+        controller.intent = if respawning { Vec2::ZERO } else { intent };
     }
 }
 
+/// Where the player reappears after dying, in world coordinates: the last
+/// checkpoint they touched, or where they entered the level.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Resource)]
+pub struct RespawnPoint(pub Vec2);
+
 /// Holds handles to player sprite assets.
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, Default)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
     #[dependency]
-    pub idle: Handle<Image>,
-}
-
-impl FromWorld for PlayerAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            idle: assets.load("images/entities/player/player_idle.png"),
-        }
-    }
+    pub animation: Handle<SpriteAnimation>,
 }
@@ -0,0 +1,214 @@
+//! Levels, and moving between them.
+//!
+//! The [`LevelRegistry`] lists every level in the order they're played. Each
+//! level has a manifest for its [`LevelAssets`], which names its map. The
+//! [`LevelProgression`] tracks which level is being played and which levels the
+//! player has reached so far.
+//!
+//! Maps lead to other levels with rectangle objects of the class [`EXIT_CLASS`].
+//! Their `level` property names the level to go to, and their optional `spawn`
+//! property names the spawn point to arrive at (see [`SPAWN_CLASS`]). Taking an
+//! exit goes back through the loading screen, which tears down the current
+//! level, loads the next one's assets and spawns it. Exits to the level that
+//! comes next in the registry complete the current level, so they show the
+//! level complete screen first.
+
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, start_loading_resource, unload_resource},
+    demo::{level::LevelAssets, player::Player},
+    screens::{InGame, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LevelRegistry>();
+    app.init_resource::<LevelRegistry>();
+    app.register_type::<LevelProgression>();
+    app.init_resource::<LevelProgression>();
+    app.register_type::<CollectedItems>();
+    app.init_resource::<CollectedItems>();
+    app.register_type::<LevelExit>();
+
+    app.init_resource_collection::<LevelAssets>();
+    app.add_systems(OnEnter(Screen::Loading), load_current_level);
+    app.add_systems(OnExit(InGame), unload_resource::<LevelAssets>);
+
+    app.add_systems(
+        Update,
+        take_level_exits
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The Tiled object class that marks an exit to another level.
+pub const EXIT_CLASS: &str = "Exit";
+
+/// The Tiled object class that marks a named spawn point. A level starts at
+/// the spawn point named `start`, or its first spawn point if none is.
+pub const SPAWN_CLASS: &str = "Spawn";
+
+/// A level that can be played.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelInfo {
+    /// How exits and saves refer to the level.
+    pub id: &'static str,
+    /// The name shown to the player.
+    pub name: &'static str,
+    /// The manifest of the level's [`LevelAssets`].
+    pub manifest: &'static str,
+}
+
+/// Every level in the game, in the order they're played.
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct LevelRegistry(pub Vec<LevelInfo>);
+
+impl Default for LevelRegistry {
+    fn default() -> Self {
+        Self(vec![
+            LevelInfo {
+                id: "meadow",
+                name: "Meadow",
+                manifest: "manifests/levels/meadow.assets.ron",
+            },
+            LevelInfo {
+                id: "hills",
+                name: "Hills",
+                manifest: "manifests/levels/hills.assets.ron",
+            },
+        ])
+    }
+}
+
+impl LevelRegistry {
+    /// The level with the given id.
+    pub fn get(&self, id: &str) -> Option<&LevelInfo> {
+        self.0.iter().find(|level| level.id == id)
+    }
+
+    /// The level played after the given one, if there is one.
+    pub fn next_after(&self, id: &str) -> Option<&LevelInfo> {
+        let index = self.0.iter().position(|level| level.id == id)?;
+        self.0.get(index + 1)
+    }
+}
+
+/// Which level is being played, and which levels the player can pick from the
+/// level select.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct LevelProgression {
+    /// The level being played, or about to be.
+    pub current: String,
+    /// The spawn point or checkpoint to enter the current level at, or `None`
+    /// for the level's start.
+    pub entry: Option<String>,
+    /// The levels the player has reached, in the order they were reached.
+    pub unlocked: Vec<String>,
+}
+
+impl FromWorld for LevelProgression {
+    fn from_world(world: &mut World) -> Self {
+        let first = world
+            .resource::<LevelRegistry>()
+            .0
+            .first()
+            .map_or_else(String::new, |level| level.id.to_string());
+        Self {
+            current: first.clone(),
+            entry: None,
+            unlocked: vec![first],
+        }
+    }
+}
+
+impl LevelProgression {
+    /// Move on to `level`, arriving at the spawn point named `entry`, and unlock it.
+    /// Takes effect the next time the loading screen is entered.
+    pub fn go_to(&mut self, level: &str, entry: Option<String>) {
+        self.current = level.to_string();
+        self.entry = entry;
+        if !self.is_unlocked(level) {
+            self.unlocked.push(level.to_string());
+        }
+    }
+
+    pub fn is_unlocked(&self, level: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == level)
+    }
+}
+
+/// The ids of the items the player has collected. Saved along with the game.
+#[derive(Resource, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct CollectedItems(pub BTreeSet<String>);
+
+/// Touching this takes the player to another level.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct LevelExit {
+    /// The id of the level to go to.
+    pub level: String,
+    /// The spawn point to arrive at, or `None` for the level's start.
+    pub spawn: Option<String>,
+    /// The size of the exit, centered on its entity.
+    pub size: Vec2,
+}
+
+/// Start loading the current level's assets, dropping the last level's.
+fn load_current_level(world: &mut World) {
+    unload_resource::<LevelAssets>(world);
+    let progression = world.resource::<LevelProgression>();
+    let registry = world.resource::<LevelRegistry>();
+    let Some(level) = registry
+        .get(&progression.current)
+        .or_else(|| registry.0.first())
+    else {
+        error!("There are no levels to load");
+        return;
+    };
+    let (id, manifest) = (level.id, level.manifest);
+    if id != progression.current {
+        warn!(
+            "Unknown level `{}`, loading `{id}` instead",
+            progression.current
+        );
+        world.resource_mut::<LevelProgression>().go_to(id, None);
+    }
+    start_loading_resource::<LevelAssets>(world, manifest);
+}
+
+fn take_level_exits(
+    registry: Res<LevelRegistry>,
+    mut progression: ResMut<LevelProgression>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    exit_query: Query<(&LevelExit, &GlobalTransform)>,
+) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+    let position = player.translation().truncate();
+    let Some(exit) = exit_query.iter().find_map(|(exit, transform)| {
+        Rect::from_center_size(transform.translation().truncate(), exit.size)
+            .contains(position)
+            .then_some(exit)
+    }) else {
+        return;
+    };
+    let completed = registry
+        .next_after(&progression.current)
+        .is_some_and(|next| next.id == exit.level);
+    progression.go_to(&exit.level, exit.spawn.clone());
+    next_screen.set(if completed {
+        Screen::LevelComplete
+    } else {
+        Screen::Loading
+    });
+}
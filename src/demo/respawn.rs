@@ -0,0 +1,247 @@
+//! What happens when the player dies.
+//!
+//! The screen fades to black, and while it's dark the player is moved back to
+//! the [`RespawnPoint`] with full health and [`Respawned`] is triggered. Other
+//! entities opt into being reset at that point with [`ResetOnRespawn`]. Then
+//! the screen fades back in.
+//!
+//! Each respawn costs one of the player's [`Lives`], which carry over from
+//! level to level and are saved with the game. Dying with none left goes to
+//! the game over screen instead.
+
+use bevy::{ecs::entity_disabling::Disabled, prelude::*, ui::Val::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        camera::CameraRig,
+        health::{Died, Health, Invulnerable},
+        movement::Knockback,
+        player::{Player, RespawnPoint},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ResetOnRespawn>();
+    app.register_type::<RespawnHome>();
+    app.register_type::<Lives>();
+    app.register_type::<Respawning>();
+    app.register_type::<RespawnFade>();
+
+    app.add_observer(record_respawn_home);
+    app.add_observer(start_respawn);
+    app.add_observer(respawn_player);
+    app.add_observer(reset_entities);
+    app.add_systems(
+        Update,
+        update_respawn_fade
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), despawn_disabled_entities);
+}
+
+/// Triggered once the player has been moved back to the [`RespawnPoint`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Respawned;
+
+/// What happens to an entity when the player respawns. Entities without this
+/// are left alone.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum ResetOnRespawn {
+    /// Removed, e.g. projectiles still in flight.
+    Despawn,
+    /// Put back where it was spawned with full health, even if it died. Until
+    /// then, dying only disables and hides it.
+    Restore,
+}
+
+/// Where a [`ResetOnRespawn::Restore`] entity was spawned.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+struct RespawnHome(Transform);
+
+/// How many more times the player can respawn. Dying without any left ends the game.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Lives(pub u32);
+
+/// Marks the player while the respawn fade plays. Their input is ignored.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct Respawning;
+
+/// The overlay that fades the screen out and back in.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+struct RespawnFade {
+    timer: Timer,
+    respawned: bool,
+    /// Go to the game over screen instead of respawning.
+    game_over: bool,
+}
+
+/// How long the screen takes to go dark, in seconds.
+const FADE_OUT_SECS: f32 = 0.4;
+/// How long the screen stays dark, in seconds.
+const HOLD_SECS: f32 = 0.15;
+/// How long the screen takes to come back, in seconds.
+const FADE_IN_SECS: f32 = 0.4;
+
+fn record_respawn_home(
+    trigger: Trigger<OnAdd, ResetOnRespawn>,
+    mut commands: Commands,
+    reset_query: Query<(&ResetOnRespawn, &Transform)>,
+) {
+    let entity = trigger.target();
+    if let Ok((ResetOnRespawn::Restore, transform)) = reset_query.get(entity) {
+        commands.entity(entity).insert(RespawnHome(*transform));
+    }
+}
+
+fn start_respawn(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    mut player_query: Query<Option<&mut Lives>, With<Player>>,
+    fade_query: Query<(), With<RespawnFade>>,
+) {
+    let entity = trigger.target();
+    if !fade_query.is_empty() {
+        return;
+    }
+    let Ok(lives) = player_query.get_mut(entity) else {
+        return;
+    };
+    let game_over = lives.is_some_and(|mut lives| {
+        let Some(remaining) = lives.0.checked_sub(1) else {
+            return true;
+        };
+        lives.0 = remaining;
+        false
+    });
+    commands.entity(entity).insert(Respawning);
+    commands.spawn((
+        Name::new("Respawn Fade"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            ..default()
+        },
+        GlobalZIndex(1),
+        BackgroundColor(Color::NONE),
+        RespawnFade {
+            timer: Timer::from_seconds(FADE_OUT_SECS + HOLD_SECS + FADE_IN_SECS, TimerMode::Once),
+            respawned: false,
+            game_over,
+        },
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn update_respawn_fade(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut fade_query: Query<(Entity, &mut RespawnFade, &mut BackgroundColor)>,
+) {
+    for (entity, mut fade, mut background) in &mut fade_query {
+        fade.timer.tick(time.delta());
+        let elapsed = fade.timer.elapsed_secs();
+        if !fade.respawned && elapsed >= FADE_OUT_SECS {
+            fade.respawned = true;
+            if fade.game_over {
+                next_screen.set(Screen::GameOver);
+            } else {
+                commands.trigger(Respawned);
+            }
+        }
+
+        let alpha = if elapsed < FADE_OUT_SECS {
+            elapsed / FADE_OUT_SECS
+        } else {
+            1.0 - (elapsed - FADE_OUT_SECS - HOLD_SECS).max(0.0) / FADE_IN_SECS
+        };
+        background.0 = Color::BLACK.with_alpha(alpha.clamp(0.0, 1.0));
+        if fade.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn respawn_player(
+    _: Trigger<Respawned>,
+    mut commands: Commands,
+    respawn_point: Res<RespawnPoint>,
+    mut player_query: Query<
+        (Entity, &mut Health, &mut Knockback, &mut Transform),
+        With<Respawning>,
+    >,
+    mut camera_query: Query<&mut CameraRig>,
+) {
+    for (entity, mut health, mut knockback, mut transform) in &mut player_query {
+        health.heal_full();
+        knockback.0 = Vec2::ZERO;
+        transform.translation = respawn_point.0.extend(transform.translation.z);
+        commands
+            .entity(entity)
+            .remove::<Respawning>()
+            .insert(Invulnerable::from_seconds(health.invulnerability_secs));
+    }
+    // The screen is dark, so jump straight to the player rather than panning.
+    for mut rig in &mut camera_query {
+        rig.snap();
+    }
+}
+
+fn reset_entities(
+    _: Trigger<Respawned>,
+    mut commands: Commands,
+    mut reset_query: Query<
+        (
+            Entity,
+            &ResetOnRespawn,
+            Option<&RespawnHome>,
+            Option<&mut Health>,
+            Option<&mut Knockback>,
+            Has<Disabled>,
+        ),
+        Without<Player>,
+    >,
+) {
+    for (entity, reset, home, health, knockback, disabled) in &mut reset_query {
+        match reset {
+            ResetOnRespawn::Despawn => {
+                commands.entity(entity).despawn();
+            }
+            ResetOnRespawn::Restore => {
+                if let Some(mut health) = health {
+                    health.heal_full();
+                }
+                if let Some(mut knockback) = knockback {
+                    knockback.0 = Vec2::ZERO;
+                }
+                let mut entity = commands.entity(entity);
+                if let Some(home) = home {
+                    entity.insert(home.0);
+                }
+                if disabled {
+                    entity.remove::<Disabled>().insert(Visibility::Inherited);
+                }
+            }
+        }
+    }
+}
+
+/// Disabled entities are invisible to [`StateScoped`] cleanup, so despawn them here.
+fn despawn_disabled_entities(
+    mut commands: Commands,
+    disabled_query: Query<Entity, (With<ResetOnRespawn>, With<Disabled>)>,
+) {
+    for entity in &disabled_query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,79 @@
+//! How the current level is going, for the screens shown when it ends.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        collectible::ItemCollected, health::Died, player::Player, progression::LevelProgression,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LevelStats>();
+    app.init_resource::<LevelStats>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_level_stats);
+    app.add_systems(
+        Update,
+        count_level_time
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+    app.add_observer(count_deaths);
+    app.add_observer(count_collected);
+}
+
+/// Statistics for the level being played, or the one that just ended. Reset
+/// whenever a level starts.
+#[derive(Resource, Reflect, Debug, Default, Clone)]
+#[reflect(Resource)]
+pub struct LevelStats {
+    /// The id of the level.
+    pub level: String,
+    /// How long the level has been played, not counting pauses.
+    pub time: Duration,
+    /// How many times the player has died in the level.
+    pub deaths: u32,
+    /// How many items the player has picked up in the level.
+    pub collected: u32,
+}
+
+/// A duration as hours, minutes and seconds, e.g. `1:02:03` or `2:03`.
+pub fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
+
+fn reset_level_stats(mut stats: ResMut<LevelStats>, progression: Res<LevelProgression>) {
+    *stats = LevelStats {
+        level: progression.current.clone(),
+        ..default()
+    };
+}
+
+fn count_level_time(time: Res<Time>, mut stats: ResMut<LevelStats>) {
+    stats.time += time.delta();
+}
+
+fn count_deaths(
+    trigger: Trigger<Died>,
+    mut stats: ResMut<LevelStats>,
+    player_query: Query<(), With<Player>>,
+) {
+    if player_query.contains(trigger.target()) {
+        stats.deaths += 1;
+    }
+}
+
+fn count_collected(_: Trigger<ItemCollected>, mut stats: ResMut<LevelStats>) {
+    stats.collected += 1;
+}
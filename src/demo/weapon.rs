@@ -0,0 +1,205 @@
+//! Weapons and the projectiles they fire. Give an entity a [`Weapon`] to arm
+//! it; the player's fires on [`Action::Shoot`] and is shown in the HUD.
+//!
+//! Each shot uses up a round of ammo. Firing the last round starts a longer
+//! cooldown, after which the weapon is fully reloaded.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{SoundBank, SoundPosition, sound_effect_from_bank},
+    demo::{
+        health::{ContactDamage, Team},
+        movement::MovementController,
+        player::Player,
+        respawn::{ResetOnRespawn, Respawning},
+    },
+    input::{Action, ActionState},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Weapon>();
+    app.register_type::<Aim>();
+    app.register_type::<Projectile>();
+    app.register_type::<WeaponAssets>();
+    app.load_resource::<WeaponAssets>("manifests/weapon.assets.ron");
+
+    app.add_systems(
+        Update,
+        (
+            tick_weapon_cooldowns.in_set(AppSystems::TickTimers),
+            (fire_player_weapon, move_projectiles)
+                .in_set(AppSystems::Update)
+                .run_if(in_state(Screen::Gameplay)),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+/// A weapon's ammunition and the time until it can fire again.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(Aim)]
+pub struct Weapon {
+    pub ammo: u32,
+    pub max_ammo: u32,
+    pub cooldown: Timer,
+    /// The cooldown after a shot, in seconds.
+    fire_secs: f32,
+    /// The cooldown after the last round, in seconds.
+    reload_secs: f32,
+}
+
+impl Weapon {
+    /// A loaded weapon, ready to fire.
+    pub fn new(max_ammo: u32, fire_secs: f32, reload_secs: f32) -> Self {
+        let mut cooldown = Timer::from_seconds(fire_secs, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self {
+            ammo: max_ammo,
+            max_ammo,
+            cooldown,
+            fire_secs,
+            reload_secs,
+        }
+    }
+
+    /// How far through its cooldown the weapon is, from 0 (just fired) to 1 (ready).
+    pub fn readiness(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+
+    /// Use up a round if the weapon is ready, returning whether it fired.
+    fn try_fire(&mut self) -> bool {
+        if !self.cooldown.finished() || self.ammo == 0 {
+            return false;
+        }
+        self.ammo -= 1;
+        let secs = if self.ammo == 0 {
+            self.reload_secs
+        } else {
+            self.fire_secs
+        };
+        self.cooldown = Timer::from_seconds(secs, TimerMode::Once);
+        true
+    }
+}
+
+/// The direction a [`Weapon`] fires in.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Aim(pub Vec2);
+
+impl Default for Aim {
+    fn default() -> Self {
+        Self(Vec2::X)
+    }
+}
+
+/// Flies in a straight line until it hits something or its time runs out.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+struct Projectile {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+#[derive(Resource, Asset, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct WeaponAssets {
+    #[dependency]
+    projectile: Handle<Image>,
+    #[dependency]
+    shoot: Handle<SoundBank>,
+}
+
+const PROJECTILE_SPEED: f32 = 700.0;
+const PROJECTILE_LIFETIME_SECS: f32 = 0.6;
+const PROJECTILE_DAMAGE: u32 = 1;
+const PROJECTILE_KNOCKBACK: f32 = 250.0;
+/// The projectile's hitbox, in world units (before scaling).
+const PROJECTILE_SIZE: Vec2 = Vec2::splat(4.0);
+
+/// A projectile fired by `team` from `position`, flying along `direction`.
+fn projectile(
+    weapon_assets: &WeaponAssets,
+    team: Team,
+    position: Vec2,
+    direction: Vec2,
+) -> impl Bundle {
+    (
+        Name::new("Projectile"),
+        Projectile {
+            velocity: direction * PROJECTILE_SPEED,
+            lifetime: Timer::from_seconds(PROJECTILE_LIFETIME_SECS, TimerMode::Once),
+        },
+        Sprite::from_image(weapon_assets.projectile.clone()),
+        Transform::from_translation(position.extend(1.0))
+            .with_rotation(Quat::from_rotation_z(direction.to_angle()))
+            .with_scale(Vec2::splat(2.0).extend(1.0)),
+        ContactDamage {
+            amount: PROJECTILE_DAMAGE,
+            knockback: PROJECTILE_KNOCKBACK,
+            size: PROJECTILE_SIZE,
+            despawn_on_hit: true,
+        },
+        team,
+        ResetOnRespawn::Despawn,
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn tick_weapon_cooldowns(time: Res<Time>, mut weapon_query: Query<&mut Weapon>) {
+    for mut weapon in &mut weapon_query {
+        // Don't mark weapons as changed once they're ready.
+        if weapon.cooldown.finished() {
+            continue;
+        }
+        weapon.cooldown.tick(time.delta());
+        if weapon.cooldown.finished() && weapon.ammo == 0 {
+            weapon.ammo = weapon.max_ammo;
+        }
+    }
+}
+
+/// Aim where the player is walking, and fire when asked.
+fn fire_player_weapon(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    weapon_assets: Res<WeaponAssets>,
+    mut player_query: Query<
+        (&MovementController, &GlobalTransform, &mut Aim, &mut Weapon),
+        (With<Player>, Without<Respawning>),
+    >,
+) {
+    for (controller, transform, mut aim, mut weapon) in &mut player_query {
+        if controller.intent != Vec2::ZERO {
+            aim.0 = controller.intent.normalize();
+        }
+        if !action_state.just_pressed(Action::Shoot) || !weapon.try_fire() {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        commands.spawn(projectile(&weapon_assets, Team::Player, position, aim.0));
+        commands.spawn((
+            sound_effect_from_bank(weapon_assets.shoot.clone()),
+            SoundPosition::At(position),
+        ));
+    }
+}
+
+fn move_projectiles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform)>,
+) {
+    for (entity, mut projectile, mut transform) in &mut projectile_query {
+        transform.translation += (projectile.velocity * time.delta_secs()).extend(0.0);
+        if projectile.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Action-based input.
+//!
+//! Game code asks about [`Action`]s instead of raw keys, so the same action can
+//! be triggered from the keyboard, the mouse or a gamepad, and players can
+//! rebind it. The [`InputMap`] says which inputs trigger which action, and the
+//! [`ActionState`] holds the result for the current frame.
+//!
+//! Read actions with the [`ActionState`] resource, or use run conditions such
+//! as [`action_just_pressed`].
+
+use bevy::{input::InputSystem, platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InputMap>();
+    app.init_resource::<InputMap>();
+    app.init_resource::<ActionState>();
+
+    // Update actions as soon as Bevy has processed this frame's input, so that
+    // systems in `AppSystems::RecordInput` and every run condition see them.
+    app.add_systems(PreUpdate, update_action_state.after(InputSystem));
+}
+
+/// Something the player can do.
+#[derive(
+    Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Dash,
+    Shoot,
+    Pause,
+    Back,
+    Confirm,
+    Retry,
+}
+
+impl Action {
+    pub const ALL: [Self; 11] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Jump,
+        Self::Dash,
+        Self::Shoot,
+        Self::Pause,
+        Self::Back,
+        Self::Confirm,
+        Self::Retry,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::Jump => "Jump",
+            Self::Dash => "Dash",
+            Self::Shoot => "Shoot",
+            Self::Pause => "Pause",
+            Self::Back => "Back",
+            Self::Confirm => "Confirm",
+            Self::Retry => "Retry",
+        }
+    }
+
+    /// Whether two actions can be pressed in the same situation, so they
+    /// shouldn't share a binding. Menu actions may share bindings with gameplay
+    /// actions, except for [`Action::Back`], which also opens the pause menu.
+    pub fn conflicts_with(self, other: Self) -> bool {
+        self != other
+            && (self.is_menu_action() == other.is_menu_action()
+                || self == Self::Back
+                || other == Self::Back)
+    }
+
+    fn is_menu_action(self) -> bool {
+        matches!(self, Self::Back | Self::Confirm | Self::Retry)
+    }
+}
+
+/// A physical input that can trigger an [`Action`].
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    /// One direction of a gamepad axis, e.g. pushing the left stick to the left.
+    GamepadAxis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+impl Binding {
+    /// A short name to show the player.
+    pub fn label(self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            Self::Mouse(button) => format!("Mouse {button:?}"),
+            Self::GamepadButton(button) => format!("Pad {button:?}"),
+            Self::GamepadAxis { axis, positive } => {
+                format!("{axis:?} {}", if positive { "+" } else { "-" })
+            }
+        }
+    }
+}
+
+/// Which [`Binding`]s trigger each [`Action`].
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct InputMap {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+    /// Gamepad axis values closer to zero than this are ignored.
+    pub axis_dead_zone: f32,
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Put `binding` in the given slot of `action`, or add it if the slot is empty.
+    pub fn set(&mut self, action: Action, slot: usize, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        match bindings.get_mut(slot) {
+            Some(existing) => *existing = binding,
+            None => bindings.push(binding),
+        }
+    }
+
+    /// Remove the binding in the given slot of `action`, if there is one.
+    pub fn clear(&mut self, action: Action, slot: usize) {
+        let bindings = self.bindings.entry(action).or_default();
+        if slot < bindings.len() {
+            bindings.remove(slot);
+        }
+    }
+
+    /// Another action that `binding` would conflict with if it were bound to `action`.
+    pub fn conflict(&self, action: Action, binding: Binding) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|&other| action.conflicts_with(other) && self.bindings(other).contains(&binding))
+    }
+
+    /// Bind `binding` to the given slot of `action`, and give the binding that
+    /// slot had to `other`, which currently uses `binding`.
+    pub fn swap(&mut self, action: Action, slot: usize, binding: Binding, other: Action) {
+        let previous = self.bindings(action).get(slot).copied();
+        let other_bindings = self.bindings.entry(other).or_default();
+        if let Some(index) = other_bindings.iter().position(|&b| b == binding) {
+            match previous {
+                Some(previous) => other_bindings[index] = previous,
+                None => {
+                    other_bindings.remove(index);
+                }
+            }
+        }
+        self.set(action, slot, binding);
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let key = Binding::Key;
+        let mouse = Binding::Mouse;
+        let button = Binding::GamepadButton;
+        let stick = |axis, positive| Binding::GamepadAxis { axis, positive };
+        Self {
+            bindings: HashMap::from_iter([
+                (
+                    Action::MoveUp,
+                    vec![
+                        key(KeyCode::KeyW),
+                        key(KeyCode::ArrowUp),
+                        button(GamepadButton::DPadUp),
+                        stick(GamepadAxis::LeftStickY, true),
+                    ],
+                ),
+                (
+                    Action::MoveDown,
+                    vec![
+                        key(KeyCode::KeyS),
+                        key(KeyCode::ArrowDown),
+                        button(GamepadButton::DPadDown),
+                        stick(GamepadAxis::LeftStickY, false),
+                    ],
+                ),
+                (
+                    Action::MoveLeft,
+                    vec![
+                        key(KeyCode::KeyA),
+                        key(KeyCode::ArrowLeft),
+                        button(GamepadButton::DPadLeft),
+                        stick(GamepadAxis::LeftStickX, false),
+                    ],
+                ),
+                (
+                    Action::MoveRight,
+                    vec![
+                        key(KeyCode::KeyD),
+                        key(KeyCode::ArrowRight),
+                        button(GamepadButton::DPadRight),
+                        stick(GamepadAxis::LeftStickX, true),
+                    ],
+                ),
+                (
+                    Action::Jump,
+                    vec![key(KeyCode::Space), button(GamepadButton::South)],
+                ),
+                (
+                    Action::Dash,
+                    vec![
+                        key(KeyCode::ShiftLeft),
+                        mouse(MouseButton::Right),
+                        button(GamepadButton::RightTrigger),
+                    ],
+                ),
+                (
+                    Action::Shoot,
+                    vec![
+                        key(KeyCode::KeyJ),
+                        mouse(MouseButton::Left),
+                        button(GamepadButton::West),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![key(KeyCode::KeyP), button(GamepadButton::Start)],
+                ),
+                (
+                    Action::Back,
+                    vec![key(KeyCode::Escape), button(GamepadButton::East)],
+                ),
+                (
+                    Action::Confirm,
+                    vec![key(KeyCode::Enter), button(GamepadButton::South)],
+                ),
+                (
+                    Action::Retry,
+                    vec![key(KeyCode::KeyR), button(GamepadButton::North)],
+                ),
+            ]),
+            axis_dead_zone: 0.25,
+        }
+    }
+}
+
+/// The state of every [`Action`] this frame.
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    actions: HashMap<Action, ActionData>,
+    /// While suspended, no action is reported as held or pressed.
+    suspended: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct ActionData {
+    /// How strongly the action is held, from 0 to 1. Digital inputs are either 0 or 1.
+    value: f32,
+    pressed: bool,
+    just_pressed: bool,
+}
+
+impl ActionState {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.data(action).just_pressed
+    }
+
+    /// How strongly the action is held, from 0 to 1.
+    pub fn value(&self, action: Action) -> f32 {
+        self.data(action).value
+    }
+
+    /// The movement direction from the four move actions, with a length of at most 1.
+    pub fn move_axis(&self) -> Vec2 {
+        Vec2::new(
+            self.value(Action::MoveRight) - self.value(Action::MoveLeft),
+            self.value(Action::MoveUp) - self.value(Action::MoveDown),
+        )
+        .clamp_length_max(1.0)
+    }
+
+    /// Ignore all actions, e.g. while the player is choosing a new binding.
+    /// Inputs held when the suspension ends don't count as newly pressed.
+    pub fn set_suspended(&mut self, suspended: bool) {
+        self.suspended = suspended;
+    }
+
+    fn data(&self, action: Action) -> ActionData {
+        self.actions.get(&action).copied().unwrap_or_default()
+    }
+}
+
+/// A run condition that is true on the frame an action starts being held.
+pub fn action_just_pressed(action: Action) -> impl FnMut(Res<ActionState>) -> bool + Clone {
+    move |action_state: Res<ActionState>| action_state.just_pressed(action)
+}
+
+fn update_action_state(
+    input_map: Res<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut action_state: ResMut<ActionState>,
+) {
+    for action in Action::ALL {
+        let value = input_map
+            .bindings(action)
+            .iter()
+            .map(|binding| match *binding {
+                Binding::Key(key) => digital(keys.pressed(key)),
+                Binding::Mouse(button) => digital(mouse_buttons.pressed(button)),
+                Binding::GamepadButton(button) => {
+                    digital(gamepads.iter().any(|gamepad| gamepad.pressed(button)))
+                }
+                Binding::GamepadAxis { axis, positive } => gamepads
+                    .iter()
+                    .filter_map(|gamepad| gamepad.get(axis))
+                    .map(|value| {
+                        let value = if positive { value } else { -value };
+                        apply_dead_zone(value, input_map.axis_dead_zone)
+                    })
+                    .fold(0.0, f32::max),
+            })
+            .fold(0.0, f32::max);
+
+        let suspended = action_state.suspended;
+        let data = action_state.actions.entry(action).or_default();
+        let was_pressed = data.pressed;
+        data.pressed = value > 0.0;
+        data.just_pressed = data.pressed && !was_pressed && !suspended;
+        data.value = if suspended { 0.0 } else { value };
+    }
+}
+
+fn digital(pressed: bool) -> f32 {
+    if pressed { 1.0 } else { 0.0 }
+}
+
+/// Ignore small values and rescale the rest so the output still covers 0 to 1.
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value <= dead_zone {
+        0.0
+    } else {
+        ((value - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn dead_zone_ignores_small_values() {
+        assert_eq!(apply_dead_zone(0.0, 0.25), 0.0);
+        assert_eq!(apply_dead_zone(0.25, 0.25), 0.0);
+        assert_eq!(apply_dead_zone(-0.8, 0.25), 0.0);
+    }
+
+    #[test]
+    fn dead_zone_rescales_the_rest() {
+        assert_eq!(apply_dead_zone(0.625, 0.25), 0.5);
+        assert_eq!(apply_dead_zone(1.0, 0.25), 1.0);
+        assert_eq!(apply_dead_zone(1.5, 0.25), 1.0);
+    }
+
+    /// The [`ActionState`] after one update with the left stick at `stick`.
+    fn state_with_left_stick(stick: Vec2) -> ActionState {
+        let mut world = World::new();
+        world.init_resource::<InputMap>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ActionState>();
+        let mut gamepad = Gamepad::default();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, stick.x);
+        gamepad.analog_mut().set(GamepadAxis::LeftStickY, stick.y);
+        world.spawn(gamepad);
+
+        world.run_system_once(update_action_state).unwrap();
+        world.remove_resource::<ActionState>().unwrap()
+    }
+
+    #[test]
+    fn stick_inside_the_dead_zone_does_nothing() {
+        let state = state_with_left_stick(Vec2::new(-0.2, 0.1));
+        assert_eq!(state.move_axis(), Vec2::ZERO);
+        assert!(!state.just_pressed(Action::MoveLeft));
+    }
+
+    #[test]
+    fn stick_past_the_dead_zone_moves_each_way() {
+        let state = state_with_left_stick(Vec2::new(-1.0, 0.625));
+        assert_eq!(state.value(Action::MoveLeft), 1.0);
+        assert_eq!(state.value(Action::MoveRight), 0.0);
+        assert_eq!(state.value(Action::MoveUp), 0.5);
+        assert_eq!(state.value(Action::MoveDown), 0.0);
+        assert!(state.just_pressed(Action::MoveLeft));
+    }
+
+    #[test]
+    fn actions_used_together_conflict() {
+        assert!(Action::Jump.conflicts_with(Action::Dash));
+        assert!(Action::Confirm.conflicts_with(Action::Back));
+        assert!(!Action::Jump.conflicts_with(Action::Jump));
+    }
+
+    #[test]
+    fn menu_actions_share_bindings_with_gameplay_except_back() {
+        assert!(!Action::Confirm.conflicts_with(Action::Jump));
+        assert!(Action::Back.conflicts_with(Action::Pause));
+        assert!(Action::Shoot.conflicts_with(Action::Back));
+    }
+
+    #[test]
+    fn finds_the_conflicting_action() {
+        let input_map = InputMap::default();
+        let space = Binding::Key(KeyCode::Space);
+        assert_eq!(input_map.conflict(Action::Dash, space), Some(Action::Jump));
+        assert_eq!(input_map.conflict(Action::Jump, space), None);
+        // Confirm shares South with Jump, but menus and gameplay don't overlap.
+        let south = Binding::GamepadButton(GamepadButton::South);
+        assert_eq!(input_map.conflict(Action::Confirm, south), None);
+    }
+
+    #[test]
+    fn swap_gives_the_other_action_the_replaced_binding() {
+        let mut input_map = InputMap::default();
+        let space = Binding::Key(KeyCode::Space);
+        let shift = Binding::Key(KeyCode::ShiftLeft);
+        input_map.swap(Action::Dash, 0, space, Action::Jump);
+        assert_eq!(input_map.bindings(Action::Dash)[0], space);
+        assert_eq!(input_map.bindings(Action::Jump)[0], shift);
+        assert_eq!(input_map.conflict(Action::Dash, space), None);
+    }
+
+    #[test]
+    fn swap_into_an_empty_slot_removes_the_other_binding() {
+        let mut input_map = InputMap::default();
+        let space = Binding::Key(KeyCode::Space);
+        input_map.swap(Action::Dash, 10, space, Action::Jump);
+        assert_eq!(input_map.bindings(Action::Dash).last(), Some(&space));
+        assert!(!input_map.bindings(Action::Jump).contains(&space));
+    }
+}
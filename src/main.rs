@@ -3,13 +3,18 @@
 // Disable console on Windows for non-dev builds.
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
+mod accessibility;
 mod asset_tracking;
 mod audio;
 mod demo;
 #[cfg(feature = "dev")]
 mod dev_tools;
+mod input;
 mod menus;
+mod particles;
+mod save;
 mod screens;
+mod settings;
 mod theme;
 
 use bevy::{asset::AssetMetaCheck, prelude::*};
@@ -45,13 +50,18 @@ impl Plugin for AppPlugin {
 
         // Add other plugins.
         app.add_plugins((
+            accessibility::plugin,
             asset_tracking::plugin,
             audio::plugin,
             demo::plugin,
             #[cfg(feature = "dev")]
             dev_tools::plugin,
+            input::plugin,
             menus::plugin,
+            particles::plugin,
+            save::plugin,
             screens::plugin,
+            settings::plugin,
             theme::plugin,
         ));
 
@@ -0,0 +1,327 @@
+//! The controls menu, where players rebind actions.
+//!
+//! Click a binding, then press the new key or button (or push a stick). If the
+//! input is already used by a conflicting action, the player can swap the two
+//! bindings or cancel.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    input::{Action, ActionState, Binding, InputMap, action_just_pressed},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Rebinding>();
+    app.add_systems(
+        OnEnter(Menu::Controls),
+        (
+            spawn_controls_menu,
+            update_bindings_list,
+            update_rebinding_prompt,
+        )
+            .chain(),
+    );
+    app.add_systems(OnExit(Menu::Controls), stop_rebinding);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(action_just_pressed(Action::Back)),
+            listen_for_binding,
+            (update_bindings_list, update_rebinding_prompt)
+                .run_if(resource_changed::<InputMap>.or(resource_changed::<Rebinding>)),
+        )
+            .chain()
+            .run_if(in_state(Menu::Controls)),
+    );
+    // Keep the keys pressed while rebinding from also triggering their actions.
+    app.add_systems(
+        Update,
+        suspend_actions_while_rebinding.run_if(resource_changed::<Rebinding>),
+    );
+
+    app.register_type::<BindingsList>();
+    app.register_type::<RebindingPrompt>();
+    app.register_type::<ConflictButtons>();
+}
+
+/// How many bindings each action can have.
+const BINDING_SLOTS: usize = 4;
+
+/// Gamepad axes must be pushed this far to be picked up as a new binding.
+const AXIS_LISTEN_THRESHOLD: f32 = 0.5;
+
+/// What the controls menu is currently doing.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+enum Rebinding {
+    #[default]
+    Idle,
+    /// Waiting for the player to press the new binding for this slot.
+    Listening { action: Action, slot: usize },
+    /// The new binding is already used by `other`.
+    Conflict {
+        action: Action,
+        slot: usize,
+        binding: Binding,
+        other: Action,
+    },
+}
+
+fn suspend_actions_while_rebinding(
+    rebinding: Res<Rebinding>,
+    mut action_state: ResMut<ActionState>,
+) {
+    action_state.set_suspended(*rebinding != Rebinding::Idle);
+}
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Controls Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Controls),
+        children![
+            widget::header("Controls"),
+            (
+                Name::new("Rebinding Prompt"),
+                Node {
+                    column_gap: Px(10.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                children![
+                    (widget::label(""), RebindingPrompt),
+                    (
+                        Name::new("Conflict Buttons"),
+                        Node {
+                            column_gap: Px(10.0),
+                            ..default()
+                        },
+                        ConflictButtons,
+                        children![
+                            widget::button_medium("Swap", swap_bindings),
+                            widget::button_medium("Cancel", cancel_rebinding),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                Name::new("Bindings List"),
+                Node {
+                    display: Display::Grid,
+                    row_gap: Px(6.0),
+                    column_gap: Px(10.0),
+                    grid_template_columns: vec![
+                        GridTrack::px(180.0),
+                        RepeatedGridTrack::px(BINDING_SLOTS as u16, 170.0),
+                    ],
+                    ..default()
+                },
+                BindingsList,
+            ),
+            (
+                Name::new("Controls Buttons"),
+                Node {
+                    column_gap: Px(20.0),
+                    ..default()
+                },
+                children![
+                    widget::button("Reset to defaults", reset_to_defaults),
+                    widget::button("Back", go_back_on_click),
+                ],
+            ),
+        ],
+    ));
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BindingsList;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RebindingPrompt;
+
+/// Only shown when a new binding conflicts with another action.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ConflictButtons;
+
+/// Rebuild the list whenever a binding changes, since any row may be affected by a swap.
+fn update_bindings_list(
+    mut commands: Commands,
+    input_map: Res<InputMap>,
+    rebinding: Res<Rebinding>,
+    list: Single<Entity, With<BindingsList>>,
+) {
+    let list = *list;
+    commands.entity(list).despawn_related::<Children>();
+    for action in Action::ALL {
+        commands.spawn((
+            widget::label(action.label()),
+            Node {
+                justify_self: JustifySelf::End,
+                align_self: AlignSelf::Center,
+                ..default()
+            },
+            ChildOf(list),
+        ));
+        let bindings = input_map.bindings(action);
+        for slot in 0..BINDING_SLOTS {
+            let text = match (*rebinding, bindings.get(slot)) {
+                (Rebinding::Listening { action: a, slot: s }, _) if a == action && s == slot => {
+                    "...".to_string()
+                }
+                (_, Some(binding)) => binding.label(),
+                (_, None) => "-".to_string(),
+            };
+            commands.spawn((
+                widget::button_medium(
+                    text,
+                    move |_: Trigger<Pointer<Click>>, mut rebinding: ResMut<Rebinding>| {
+                        *rebinding = Rebinding::Listening { action, slot };
+                    },
+                ),
+                ChildOf(list),
+            ));
+        }
+    }
+}
+
+fn update_rebinding_prompt(
+    rebinding: Res<Rebinding>,
+    mut prompt: Single<&mut Text, With<RebindingPrompt>>,
+    mut conflict_buttons: Single<&mut Node, With<ConflictButtons>>,
+) {
+    conflict_buttons.display = match *rebinding {
+        Rebinding::Conflict { .. } => Display::Flex,
+        _ => Display::None,
+    };
+    prompt.0 = match *rebinding {
+        Rebinding::Idle => "Click a binding to change it.".to_string(),
+        Rebinding::Listening { action, .. } => format!(
+            "Press the new binding for {}. Escape cancels, Backspace clears.",
+            action.label()
+        ),
+        Rebinding::Conflict { binding, other, .. } => {
+            format!("{} is already bound to {}.", binding.label(), other.label())
+        }
+    };
+}
+
+fn listen_for_binding(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut input_map: ResMut<InputMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Rebinding::Listening { action, slot } = *rebinding else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        *rebinding = Rebinding::Idle;
+        return;
+    }
+    if keys.just_pressed(KeyCode::Backspace) {
+        input_map.clear(action, slot);
+        *rebinding = Rebinding::Idle;
+        return;
+    }
+
+    let Some(binding) = keys
+        .get_just_pressed()
+        .next()
+        .map(|&key| Binding::Key(key))
+        .or_else(|| {
+            mouse_buttons
+                .get_just_pressed()
+                .next()
+                .map(|&button| Binding::Mouse(button))
+        })
+        .or_else(|| {
+            gamepads.iter().find_map(|gamepad| {
+                gamepad
+                    .get_just_pressed()
+                    .next()
+                    .map(|&button| Binding::GamepadButton(button))
+            })
+        })
+        .or_else(|| gamepads.iter().find_map(pushed_axis))
+    else {
+        return;
+    };
+
+    if input_map.bindings(action).contains(&binding) {
+        *rebinding = Rebinding::Idle;
+    } else if let Some(other) = input_map.conflict(action, binding) {
+        *rebinding = Rebinding::Conflict {
+            action,
+            slot,
+            binding,
+            other,
+        };
+    } else {
+        input_map.set(action, slot, binding);
+        *rebinding = Rebinding::Idle;
+    }
+}
+
+fn pushed_axis(gamepad: &Gamepad) -> Option<Binding> {
+    [
+        GamepadAxis::LeftStickX,
+        GamepadAxis::LeftStickY,
+        GamepadAxis::RightStickX,
+        GamepadAxis::RightStickY,
+    ]
+    .into_iter()
+    .find_map(|axis| {
+        let value = gamepad.get(axis)?;
+        (value.abs() > AXIS_LISTEN_THRESHOLD).then_some(Binding::GamepadAxis {
+            axis,
+            positive: value > 0.0,
+        })
+    })
+}
+
+fn swap_bindings(
+    _: Trigger<Pointer<Click>>,
+    mut input_map: ResMut<InputMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if let Rebinding::Conflict {
+        action,
+        slot,
+        binding,
+        other,
+    } = *rebinding
+    {
+        input_map.swap(action, slot, binding, other);
+    }
+    *rebinding = Rebinding::Idle;
+}
+
+fn cancel_rebinding(_: Trigger<Pointer<Click>>, mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::Idle;
+}
+
+fn reset_to_defaults(
+    _: Trigger<Pointer<Click>>,
+    mut input_map: ResMut<InputMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    *input_map = InputMap::default();
+    *rebinding = Rebinding::Idle;
+}
+
+fn stop_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::Idle;
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
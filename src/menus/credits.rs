@@ -1,21 +1,29 @@
 //! The credits menu.
 
-use bevy::{
-    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
-};
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::Val::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::MusicDirector,
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(action_just_pressed(Action::Back))),
     );
 
     app.register_type::<CreditsAssets>();
-    app.load_resource::<CreditsAssets>();
-    app.add_systems(OnEnter(Menu::Credits), start_credits_music);
+    app.load_resource_in_state::<CreditsAssets, _>("manifests/credits.assets.ron", Menu::Credits);
+    app.add_systems(
+        Update,
+        start_credits_music.run_if(resource_added::<CreditsAssets>),
+    );
+    app.add_systems(OnExit(Menu::Credits), stop_credits_music);
 }
 
 fn spawn_credits_menu(mut commands: Commands) {
@@ -88,26 +96,20 @@ fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Main);
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, Default)]
 #[reflect(Resource)]
 struct CreditsAssets {
     #[dependency]
     music: Handle<AudioSource>,
 }
 
-impl FromWorld for CreditsAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/music/Monkeys Spinning Monkeys.ogg"),
-        }
-    }
+fn start_credits_music(
+    mut music_director: ResMut<MusicDirector>,
+    credits_music: Res<CreditsAssets>,
+) {
+    music_director.play(credits_music.music.clone());
 }
 
-fn start_credits_music(mut commands: Commands, credits_music: Res<CreditsAssets>) {
-    commands.spawn((
-        Name::new("Credits Music"),
-        StateScoped(Menu::Credits),
-        music(credits_music.music.clone()),
-    ));
+fn stop_credits_music(mut music_director: ResMut<MusicDirector>) {
+    music_director.stop();
 }
@@ -0,0 +1,98 @@
+//! The level select menu, listing the levels the player has reached.
+//!
+//! Unless a game is already going, the levels come from the most recent save,
+//! which is only loaded once a level is picked. Without a save, picking a level
+//! starts a new game in the first empty slot.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::progression::{LevelProgression, LevelRegistry},
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    save::{ActiveSave, SaveFile, load_game, new_game},
+    screens::Screen,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::LevelSelect), spawn_level_select_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::LevelSelect).and(action_just_pressed(Action::Back))),
+    );
+}
+
+fn spawn_level_select_menu(
+    mut commands: Commands,
+    registry: Res<LevelRegistry>,
+    progression: Res<LevelProgression>,
+    active_save: Res<ActiveSave>,
+) {
+    // Only read the latest save here, so backing out doesn't start playing it.
+    let latest_save = active_save
+        .slot
+        .is_none()
+        .then(SaveFile::latest_slot)
+        .flatten()
+        .and_then(|slot| Some((slot, SaveFile::load(slot).ok()??)));
+    let slot = latest_save.as_ref().map(|(slot, _)| *slot);
+    let levels: Vec<_> = registry
+        .0
+        .iter()
+        .filter(|level| {
+            progression.is_unlocked(level.id)
+                || latest_save
+                    .as_ref()
+                    .is_some_and(|(_, save)| save.unlocked.iter().any(|id| id == level.id))
+        })
+        .map(|level| {
+            let id = level.id;
+            widget::button(
+                level.name,
+                move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    commands.queue(move |world: &mut World| play_level(world, slot, id));
+                },
+            )
+        })
+        .collect();
+
+    commands
+        .spawn((
+            widget::ui_root("Level Select Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::LevelSelect),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Levels"));
+            for level in levels {
+                parent.spawn(level);
+            }
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+/// Play the level `id`, continuing the game saved in `slot` if there is one.
+fn play_level(world: &mut World, slot: Option<usize>, id: &str) {
+    if let Some(slot) = slot {
+        load_game(world, slot);
+    } else if world.resource::<ActiveSave>().slot.is_none() {
+        let empty_slot = SaveFile::load_all()
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(0);
+        new_game(world, empty_slot);
+    }
+    world.resource_mut::<LevelProgression>().go_to(id, None);
+    world
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Loading);
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -2,45 +2,60 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    menus::Menu,
+    save::{SaveFile, load_game},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
 fn spawn_main_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::ui_root("Main Menu"),
-        GlobalZIndex(2),
-        StateScoped(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
-    ));
-}
-
-fn enter_loading_or_gameplay_screen(
-    _: Trigger<Pointer<Click>>,
-    resource_handles: Res<ResourceHandles>,
-    mut next_screen: ResMut<NextState<Screen>>,
-) {
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Gameplay);
-    } else {
-        next_screen.set(Screen::Loading);
+    let latest_save = SaveFile::latest_slot();
+    commands
+        .spawn((
+            widget::ui_root("Main Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Main),
+        ))
+        .with_children(|parent| {
+            if let Some(slot) = latest_save {
+                parent.spawn(widget::button(
+                    "Continue",
+                    move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.queue(move |world: &mut World| continue_game(world, slot));
+                    },
+                ));
+            }
+            parent.spawn(widget::button("Play", open_save_slots_menu));
+            parent.spawn(widget::button("Levels", open_level_select_menu));
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            parent.spawn(widget::button("Credits", open_credits_menu));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Exit", exit_app));
+        });
+}
+
+/// Load the game saved in `slot` and play it, if it's still there.
+fn continue_game(world: &mut World, slot: usize) {
+    if load_game(world, slot) {
+        world
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Loading);
     }
 }
 
+fn open_save_slots_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn open_level_select_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::LevelSelect);
+}
+
 fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
@@ -1,8 +1,11 @@
 //! The game's menus and transitions between them.
 
+mod controls;
 mod credits;
+mod level_select;
 mod main;
 mod pause;
+mod save_slots;
 mod settings;
 
 use bevy::prelude::*;
@@ -11,8 +14,11 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        controls::plugin,
         credits::plugin,
+        level_select::plugin,
         main::plugin,
+        save_slots::plugin,
         settings::plugin,
         pause::plugin,
     ));
@@ -25,6 +31,9 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    LevelSelect,
+    SaveSlots,
     Settings,
+    Controls,
     Pause,
 }
@@ -0,0 +1,123 @@
+//! The save slots menu, where players pick a saved game or start a new one.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    demo::{progression::LevelRegistry, stats::format_time},
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    save::{SAVE_SLOTS, SaveFile, load_game, new_game},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::SaveSlots), spawn_save_slots_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::SaveSlots).and(action_just_pressed(Action::Back))),
+    );
+}
+
+fn spawn_save_slots_menu(mut commands: Commands, registry: Res<LevelRegistry>) {
+    let saves = SaveFile::load_all();
+    commands
+        .spawn((
+            widget::ui_root("Save Slots Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::SaveSlots),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Save slots"));
+            parent
+                .spawn((
+                    Name::new("Slots Grid"),
+                    Node {
+                        display: Display::Grid,
+                        row_gap: Px(10.0),
+                        column_gap: Px(30.0),
+                        align_items: AlignItems::Center,
+                        grid_template_columns: vec![
+                            GridTrack::px(460.0),
+                            GridTrack::auto(),
+                            GridTrack::auto(),
+                        ],
+                        ..default()
+                    },
+                ))
+                .with_children(|grid| {
+                    for (slot, save) in saves.iter().enumerate().take(SAVE_SLOTS) {
+                        grid.spawn(widget::label(describe_slot(slot, save.as_ref(), &registry)));
+                        if save.is_some() {
+                            grid.spawn(widget::button_medium(
+                                "Load",
+                                move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                                    commands.queue(move |world: &mut World| {
+                                        if load_game(world, slot) {
+                                            enter_loading_screen(world);
+                                        }
+                                    });
+                                },
+                            ));
+                        } else {
+                            grid.spawn(Node::default());
+                        }
+                        grid.spawn(widget::button_medium(
+                            "New game",
+                            move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                                commands.queue(move |world: &mut World| {
+                                    new_game(world, slot);
+                                    enter_loading_screen(world);
+                                });
+                            },
+                        ));
+                    }
+                });
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+/// What a save slot's row says about it.
+fn describe_slot(slot: usize, save: Option<&SaveFile>, registry: &LevelRegistry) -> String {
+    let Some(save) = save else {
+        return format!("Slot {}: empty", slot + 1);
+    };
+    let level = registry
+        .get(&save.level)
+        .map_or(save.level.as_str(), |level| level.name);
+    format!(
+        "Slot {}: {level}\n{} played, saved {}",
+        slot + 1,
+        format_time(save.metadata.playtime()),
+        format_age(save.metadata.age()),
+    )
+}
+
+/// Roughly how long ago something happened, e.g. `3 hours ago`.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (amount, unit) = match secs {
+        0..60 => return "just now".to_string(),
+        60..3600 => (secs / 60, "minute"),
+        3600..86400 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural} ago")
+}
+
+fn enter_loading_screen(world: &mut World) {
+    world
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Loading);
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
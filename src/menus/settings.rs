@@ -2,21 +2,54 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{audio::Volume, ecs::spawn::SpawnableList, prelude::*, ui::Val::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    accessibility::Accessibility,
+    audio::ChannelVolumes,
+    demo::hud::HudSettings,
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    settings::{DisplayMode, MAX_UI_SCALE, MAX_VOLUME, MIN_UI_SCALE, MIN_VOLUME},
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(action_just_pressed(Action::Back))),
     );
 
-    app.register_type::<GlobalVolumeLabel>();
+    app.register_type::<VolumeLabel>();
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        update_volume_labels.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<DisplayModeLabel>();
+    app.add_systems(
+        Update,
+        update_display_mode_label.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<ReducedMotionLabel>();
+    app.add_systems(
+        Update,
+        update_reduced_motion_label.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<UiScaleLabel>();
+    app.add_systems(
+        Update,
+        update_ui_scale_label.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<LevelTimerLabel>();
+    app.add_systems(
+        Update,
+        update_level_timer_label.run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -28,6 +61,7 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -43,67 +77,293 @@ fn settings_grid() -> impl Bundle {
             grid_template_columns: RepeatedGridTrack::px(2, 400.0),
             ..default()
         },
+        Children::spawn((
+            setting_row("Master Volume", volume_widget(VolumeChannel::Master)),
+            setting_row("Music Volume", volume_widget(VolumeChannel::Music)),
+            setting_row(
+                "Sound Effects Volume",
+                volume_widget(VolumeChannel::SoundEffects),
+            ),
+            setting_row("UI Volume", volume_widget(VolumeChannel::Ui)),
+            setting_row("Display Mode", display_mode_widget()),
+            setting_row("UI Scale", ui_scale_widget()),
+            setting_row("Reduced Motion", reduced_motion_widget()),
+            setting_row("Level Timer", level_timer_widget()),
+        )),
+    )
+}
+
+/// A row of the settings grid: a label, and the widget that changes the setting.
+fn setting_row(label: &'static str, widget: impl Bundle) -> impl SpawnableList<ChildOf> {
+    (
+        Spawn((
+            widget::label(label),
+            Node {
+                justify_self: JustifySelf::End,
+                ..default()
+            },
+        )),
+        Spawn(widget),
+    )
+}
+
+/// One of the volume sliders.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeChannel {
+    Master,
+    Music,
+    SoundEffects,
+    Ui,
+}
+
+fn volume_widget(channel: VolumeChannel) -> impl Bundle {
+    (
+        Name::new("Volume Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
         children![
+            widget::button_small(
+                "-",
+                move |_: Trigger<Pointer<Click>>,
+                      global_volume: ResMut<GlobalVolume>,
+                      channel_volumes: ResMut<ChannelVolumes>| {
+                    change_volume(channel, -VOLUME_STEP, global_volume, channel_volumes);
+                }
+            ),
             (
-                widget::label("Master Volume"),
+                Name::new("Current Volume"),
                 Node {
-                    justify_self: JustifySelf::End,
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
                     ..default()
+                },
+                children![(widget::label(""), VolumeLabel(channel))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: Trigger<Pointer<Click>>,
+                      global_volume: ResMut<GlobalVolume>,
+                      channel_volumes: ResMut<ChannelVolumes>| {
+                    change_volume(channel, VOLUME_STEP, global_volume, channel_volumes);
                 }
             ),
-            global_volume_widget(),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+const VOLUME_STEP: f32 = 0.1;
+
+fn change_volume(
+    channel: VolumeChannel,
+    step: f32,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut channel_volumes: ResMut<ChannelVolumes>,
+) {
+    let change = |linear: f32| (linear + step).clamp(MIN_VOLUME, MAX_VOLUME);
+    match channel {
+        VolumeChannel::Master => {
+            global_volume.volume = Volume::Linear(change(global_volume.volume.to_linear()));
+        }
+        VolumeChannel::Music => channel_volumes.music = change(channel_volumes.music),
+        VolumeChannel::SoundEffects => {
+            channel_volumes.sound_effects = change(channel_volumes.sound_effects);
+        }
+        VolumeChannel::Ui => channel_volumes.ui = change(channel_volumes.ui),
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeLabel(VolumeChannel);
+
+fn update_volume_labels(
+    global_volume: Res<GlobalVolume>,
+    channel_volumes: Res<ChannelVolumes>,
+    mut label_query: Query<(&VolumeLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut label_query {
+        let linear = match label.0 {
+            VolumeChannel::Master => global_volume.volume.to_linear(),
+            VolumeChannel::Music => channel_volumes.music,
+            VolumeChannel::SoundEffects => channel_volumes.sound_effects,
+            VolumeChannel::Ui => channel_volumes.ui,
+        };
+        let percent = 100.0 * linear;
+        text.0 = format!("{percent:3.0}%");
+    }
+}
+
+fn display_mode_widget() -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Display Mode Widget"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small("<", previous_display_mode),
             (
-                Name::new("Current Volume"),
+                Name::new("Current Display Mode"),
                 Node {
                     padding: UiRect::horizontal(Px(10.0)),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), DisplayModeLabel)],
             ),
-            widget::button_small("+", raise_global_volume),
+            widget::button_small(">", next_display_mode),
         ],
     )
 }
 
-const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
+fn previous_display_mode(_: Trigger<Pointer<Click>>, mut display_mode: ResMut<DisplayMode>) {
+    *display_mode = display_mode.previous();
+}
+
+fn next_display_mode(_: Trigger<Pointer<Click>>, mut display_mode: ResMut<DisplayMode>) {
+    *display_mode = display_mode.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DisplayModeLabel;
+
+fn update_display_mode_label(
+    display_mode: Res<DisplayMode>,
+    mut label: Single<&mut Text, With<DisplayModeLabel>>,
+) {
+    label.0 = display_mode.label().to_string();
+}
 
-fn lower_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn reduced_motion_widget() -> impl Bundle {
+    (
+        Name::new("Reduced Motion Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_reduced_motion),
+            (
+                Name::new("Current Reduced Motion"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), ReducedMotionLabel)],
+            ),
+            widget::button_small(">", toggle_reduced_motion),
+        ],
+    )
 }
 
-fn raise_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn toggle_reduced_motion(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<Accessibility>) {
+    accessibility.reduced_motion = !accessibility.reduced_motion;
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct ReducedMotionLabel;
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn update_reduced_motion_label(
+    accessibility: Res<Accessibility>,
+    mut label: Single<&mut Text, With<ReducedMotionLabel>>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
+    label.0 = if accessibility.reduced_motion {
+        "On"
+    } else {
+        "Off"
+    }
+    .to_string();
+}
+
+fn ui_scale_widget() -> impl Bundle {
+    (
+        Name::new("UI Scale Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", shrink_ui),
+            (
+                Name::new("Current UI Scale"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), UiScaleLabel)],
+            ),
+            widget::button_small("+", grow_ui),
+        ],
+    )
+}
+
+const UI_SCALE_STEP: f32 = 0.1;
+
+fn shrink_ui(_: Trigger<Pointer<Click>>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = (ui_scale.0 - UI_SCALE_STEP).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+}
+
+fn grow_ui(_: Trigger<Pointer<Click>>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = (ui_scale.0 + UI_SCALE_STEP).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct UiScaleLabel;
+
+fn update_ui_scale_label(ui_scale: Res<UiScale>, mut label: Single<&mut Text, With<UiScaleLabel>>) {
+    let percent = 100.0 * ui_scale.0;
     label.0 = format!("{percent:3.0}%");
 }
 
+fn level_timer_widget() -> impl Bundle {
+    (
+        Name::new("Level Timer Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_level_timer),
+            (
+                Name::new("Current Level Timer"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), LevelTimerLabel)],
+            ),
+            widget::button_small(">", toggle_level_timer),
+        ],
+    )
+}
+
+fn toggle_level_timer(_: Trigger<Pointer<Click>>, mut hud_settings: ResMut<HudSettings>) {
+    hud_settings.show_timer = !hud_settings.show_timer;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LevelTimerLabel;
+
+fn update_level_timer_label(
+    hud_settings: Res<HudSettings>,
+    mut label: Single<&mut Text, With<LevelTimerLabel>>,
+) {
+    label.0 = if hud_settings.show_timer { "On" } else { "Off" }.to_string();
+}
+
+fn open_controls_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
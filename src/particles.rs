@@ -0,0 +1,415 @@
+//! A lightweight CPU-simulated 2D particle system.
+//!
+//! Effects are described in `.particles.ron` files (see `assets/particles/`).
+//! Spawn a [`particle_emitter`] wherever an effect should play. It despawns
+//! itself once it has finished emitting and all of its particles have died.
+//! Particles are scoped to the same [`Screen`] as their emitter, so leaving
+//! gameplay clears them too.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use rand::Rng;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{AppSystems, PausableSystems, asset_tracking::RegisterManifestAsset, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<ParticleEffect>();
+    app.register_asset_loader(ParticleEffectLoader);
+    app.register_manifest_asset::<ParticleEffect>();
+
+    app.register_type::<ParticleEmitter>();
+    app.register_type::<Particle>();
+    app.add_systems(
+        Update,
+        (emit_particles, update_particles)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A particle emitter playing the given effect. Add a [`Transform`] to place it.
+pub fn particle_emitter(effect: Handle<ParticleEffect>) -> impl Bundle {
+    (Name::new("Particle Emitter"), ParticleEmitter::new(effect))
+}
+
+/// A reusable particle effect preset, loaded from a `.particles.ron` file.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct ParticleEffect {
+    /// Particles spawned as soon as the emitter starts.
+    pub burst: u32,
+    /// Particles spawned per second while the emitter is active.
+    pub rate: f32,
+    /// How long the emitter keeps spawning at `rate`, in seconds.
+    pub duration: f32,
+    /// Particle lifetime range, in seconds.
+    pub lifetime: (f32, f32),
+    /// Initial speed range, in world units per second.
+    pub speed: (f32, f32),
+    /// Center of the emission cone, in radians counterclockwise from +X.
+    pub direction: f32,
+    /// Full width of the emission cone, in radians.
+    pub spread: f32,
+    /// Acceleration, in world units per second squared.
+    pub gravity: Vec2,
+    /// How quickly velocity decays, per second.
+    pub drag: f32,
+    /// Color (including alpha) keyed by normalized age.
+    pub color: Vec<(f32, Srgba)>,
+    /// Size in world units keyed by normalized age.
+    pub size: Vec<(f32, f32)>,
+    pub texture: ParticleTexture,
+}
+
+/// What a single particle looks like.
+#[derive(Debug, Clone)]
+pub enum ParticleTexture {
+    /// A plain square tinted by the effect's color.
+    Square,
+    /// A random image per particle.
+    Images(Vec<Handle<Image>>),
+    /// A random frame of a sprite sheet per particle.
+    Atlas {
+        image: Handle<Image>,
+        layout: Handle<TextureAtlasLayout>,
+        frames: Vec<usize>,
+    },
+}
+
+/// Spawns particles according to its [`ParticleEffect`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct ParticleEmitter {
+    pub effect: Handle<ParticleEffect>,
+    /// Time since the emitter started, in seconds.
+    age: f32,
+    /// Fractional particles owed by `rate` but not yet spawned.
+    pending: f32,
+    burst_done: bool,
+    /// Particles spawned by this emitter that are still alive.
+    alive: u32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: Handle<ParticleEffect>) -> Self {
+        Self {
+            effect,
+            age: 0.0,
+            pending: 0.0,
+            burst_done: false,
+            alive: 0,
+        }
+    }
+}
+
+/// A single simulated particle. Particles live in world space, so they don't
+/// follow their emitter after being spawned.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Particle {
+    emitter: Entity,
+    effect: Handle<ParticleEffect>,
+    velocity: Vec2,
+    /// Time since the particle was spawned, in seconds.
+    age: f32,
+    /// Total lifetime, in seconds.
+    lifetime: f32,
+}
+
+fn emit_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    effects: Res<Assets<ParticleEffect>>,
+    mut emitter_query: Query<(
+        Entity,
+        &mut ParticleEmitter,
+        &GlobalTransform,
+        Option<&StateScoped<Screen>>,
+    )>,
+) {
+    let rng = &mut rand::rng();
+    for (entity, mut emitter, transform, scope) in &mut emitter_query {
+        let Some(effect) = effects.get(&emitter.effect) else {
+            continue;
+        };
+
+        let mut count = 0;
+        if !emitter.burst_done {
+            emitter.burst_done = true;
+            count += effect.burst;
+        }
+        if emitter.age < effect.duration {
+            emitter.pending += effect.rate * time.delta_secs();
+            count += emitter.pending as u32;
+            emitter.pending = emitter.pending.fract();
+        }
+        emitter.age += time.delta_secs();
+
+        let origin = transform.translation();
+        for _ in 0..count {
+            let mut particle =
+                commands.spawn(spawn_particle(entity, &emitter.effect, effect, origin, rng));
+            if let Some(scope) = scope {
+                particle.insert(scope.clone());
+            }
+        }
+        emitter.alive += count;
+
+        if emitter.age >= effect.duration && emitter.alive == 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn spawn_particle(
+    emitter: Entity,
+    handle: &Handle<ParticleEffect>,
+    effect: &ParticleEffect,
+    origin: Vec3,
+    rng: &mut impl Rng,
+) -> impl Bundle {
+    let angle = effect.direction + effect.spread * rng.random_range(-0.5..=0.5);
+    let speed = random_in(rng, effect.speed);
+    let color = sample(&effect.color, 0.0, |a, b, t| a.mix(&b, t));
+    let size = sample(&effect.size, 0.0, |a, b, t| a.lerp(b, t));
+
+    let mut sprite = Sprite::from_color(color, Vec2::splat(size));
+    match &effect.texture {
+        ParticleTexture::Square => {}
+        ParticleTexture::Images(images) => {
+            sprite.image = images[rng.random_range(0..images.len())].clone();
+        }
+        ParticleTexture::Atlas {
+            image,
+            layout,
+            frames,
+        } => {
+            sprite.image = image.clone();
+            sprite.texture_atlas = Some(TextureAtlas {
+                layout: layout.clone(),
+                index: frames[rng.random_range(0..frames.len())],
+            });
+        }
+    }
+
+    (
+        Name::new("Particle"),
+        Particle {
+            emitter,
+            effect: handle.clone(),
+            velocity: Vec2::from_angle(angle) * speed,
+            age: 0.0,
+            lifetime: random_in(rng, effect.lifetime),
+        },
+        sprite,
+        Transform::from_translation(origin),
+    )
+}
+
+fn update_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    effects: Res<Assets<ParticleEffect>>,
+    mut emitter_query: Query<&mut ParticleEmitter>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in &mut particle_query {
+        particle.age += dt;
+        let Some(effect) = effects.get(&particle.effect) else {
+            continue;
+        };
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            if let Ok(mut emitter) = emitter_query.get_mut(particle.emitter) {
+                emitter.alive = emitter.alive.saturating_sub(1);
+            }
+            continue;
+        }
+
+        particle.velocity += effect.gravity * dt;
+        particle.velocity *= (-effect.drag * dt).exp();
+        transform.translation += particle.velocity.extend(0.0) * dt;
+
+        let t = particle.age / particle.lifetime;
+        sprite.color = sample(&effect.color, t, |a, b, t| a.mix(&b, t)).into();
+        sprite.custom_size = Some(Vec2::splat(sample(&effect.size, t, |a, b, t| a.lerp(b, t))));
+    }
+}
+
+fn random_in(rng: &mut impl Rng, (min, max): (f32, f32)) -> f32 {
+    if min < max {
+        rng.random_range(min..=max)
+    } else {
+        min
+    }
+}
+
+/// Sample keyframes sorted by time, holding the first and last values outside their range.
+fn sample<T: Copy>(keys: &[(f32, T)], t: f32, lerp: impl Fn(T, T, f32) -> T) -> T {
+    let next = keys.partition_point(|(key_t, _)| *key_t < t);
+    if next == 0 {
+        return keys[0].1;
+    }
+    let Some(&(t1, b)) = keys.get(next) else {
+        return keys[keys.len() - 1].1;
+    };
+    let (t0, a) = keys[next - 1];
+    lerp(a, b, (t - t0) / (t1 - t0))
+}
+
+/// The on-disk format of a [`ParticleEffect`]. Angles are in degrees.
+#[derive(Deserialize, Debug)]
+struct ParticleEffectFile {
+    #[serde(default)]
+    burst: u32,
+    #[serde(default)]
+    rate: f32,
+    #[serde(default)]
+    duration: f32,
+    lifetime: (f32, f32),
+    speed: (f32, f32),
+    #[serde(default = "default_direction")]
+    direction: f32,
+    #[serde(default = "default_spread")]
+    spread: f32,
+    #[serde(default)]
+    gravity: (f32, f32),
+    #[serde(default)]
+    drag: f32,
+    /// `(time, (red, green, blue, alpha))` keyframes in sRGB.
+    color: Vec<(f32, (f32, f32, f32, f32))>,
+    /// `(time, size)` keyframes.
+    size: Vec<(f32, f32)>,
+    #[serde(default)]
+    texture: ParticleTextureFile,
+}
+
+fn default_direction() -> f32 {
+    90.0
+}
+
+fn default_spread() -> f32 {
+    360.0
+}
+
+#[derive(Deserialize, Debug, Default)]
+enum ParticleTextureFile {
+    #[default]
+    Square,
+    /// Paths relative to the `assets` folder.
+    Images(Vec<String>),
+    Atlas {
+        image: String,
+        tile_size: (u32, u32),
+        columns: u32,
+        rows: u32,
+        /// Atlas indices to pick from. Defaults to every cell.
+        #[serde(default)]
+        frames: Vec<usize>,
+    },
+}
+
+#[derive(Default)]
+struct ParticleEffectLoader;
+
+#[derive(Debug, Error)]
+pub enum ParticleEffectLoaderError {
+    #[error("Could not read particle effect: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse particle effect: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Particle effect needs at least one {0} keyframe")]
+    MissingKeyframes(&'static str),
+    #[error("Particle effect has no images or frames to pick from")]
+    NoImages,
+}
+
+impl AssetLoader for ParticleEffectLoader {
+    type Asset = ParticleEffect;
+    type Settings = ();
+    type Error = ParticleEffectLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: ParticleEffectFile = ron::de::from_bytes(&bytes)?;
+
+        let mut color: Vec<_> = file
+            .color
+            .into_iter()
+            .map(|(t, (r, g, b, a))| (t, Srgba::new(r, g, b, a)))
+            .collect();
+        let mut size = file.size;
+        if color.is_empty() {
+            return Err(ParticleEffectLoaderError::MissingKeyframes("color"));
+        }
+        if size.is_empty() {
+            return Err(ParticleEffectLoaderError::MissingKeyframes("size"));
+        }
+        color.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        size.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let texture = match file.texture {
+            ParticleTextureFile::Square => ParticleTexture::Square,
+            ParticleTextureFile::Images(paths) => {
+                if paths.is_empty() {
+                    return Err(ParticleEffectLoaderError::NoImages);
+                }
+                ParticleTexture::Images(paths.iter().map(|path| load_context.load(path)).collect())
+            }
+            ParticleTextureFile::Atlas {
+                image,
+                tile_size,
+                columns,
+                rows,
+                frames,
+            } => {
+                let layout =
+                    TextureAtlasLayout::from_grid(tile_size.into(), columns, rows, None, None);
+                let frames = if frames.is_empty() {
+                    (0..layout.len()).collect()
+                } else {
+                    frames
+                };
+                if frames.is_empty() {
+                    return Err(ParticleEffectLoaderError::NoImages);
+                }
+                ParticleTexture::Atlas {
+                    image: load_context.load(&image),
+                    layout: load_context.add_labeled_asset("layout".to_string(), layout),
+                    frames,
+                }
+            }
+        };
+
+        Ok(ParticleEffect {
+            burst: file.burst,
+            rate: file.rate,
+            duration: file.duration,
+            lifetime: file.lifetime,
+            speed: file.speed,
+            direction: file.direction.to_radians(),
+            spread: file.spread.clamp(0.0, 360.0).to_radians(),
+            gravity: file.gravity.into(),
+            drag: file.drag,
+            color,
+            size,
+            texture,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["particles.ron"]
+    }
+}
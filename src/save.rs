@@ -0,0 +1,441 @@
+//! Saved games, kept in numbered slots.
+//!
+//! Each slot is a RON file in the platform's data directory (e.g.
+//! `~/.local/share/solz/saves/slot1.ron` on Linux). The game is saved whenever
+//! a level starts, at checkpoints, after respawning, when leaving gameplay and
+//! when quitting. On platforms without a data directory (such as the web)
+//! games simply aren't saved.
+//!
+//! Save files carry a version. When the format changes, bump it by adding a
+//! migration to [`MIGRATIONS`], so saves from older versions of the game are
+//! upgraded when they're loaded instead of being rejected.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    PausableSystems,
+    demo::{
+        health::{Died, Health},
+        player::Player,
+        progression::{CollectedItems, LevelProgression},
+        respawn::{Lives, Respawned},
+    },
+    screens::{InGame, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ActiveSave>();
+    app.add_observer(count_death);
+    app.add_observer(save_after_respawn);
+    app.add_systems(
+        Update,
+        (
+            count_playtime.in_set(PausableSystems),
+            (restore_player_health, track_player_health).chain(),
+            (restore_player_lives, track_player_lives).chain(),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), save_game);
+    app.add_systems(OnExit(Screen::Gameplay), save_game);
+    app.add_systems(OnEnter(Screen::GameOver), (refill_lives, save_game).chain());
+    // Don't lose progress made just before quitting.
+    app.add_systems(
+        Last,
+        save_game.run_if(in_state(InGame).and(on_event::<AppExit>)),
+    );
+}
+
+/// How many save slots there are.
+pub const SAVE_SLOTS: usize = 3;
+
+/// Upgrades a save file from one version to the next. The migration at index
+/// `i` turns a version `i + 1` save into a version `i + 2` save.
+type Migration = fn(&mut ron::Map);
+
+/// Every migration, oldest first. Add one whenever the save format changes.
+const MIGRATIONS: &[Migration] = &[add_deaths, add_lives];
+
+/// The current save format version.
+const SAVE_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Version 2 counts how many times the player has died.
+fn add_deaths(map: &mut ron::Map) {
+    map.insert(
+        ron::Value::String("deaths".into()),
+        ron::Value::Number(0_i64.into()),
+    );
+}
+
+/// Version 3 keeps how many lives the player has left.
+fn add_lives(map: &mut ron::Map) {
+    map.insert(ron::Value::String("lives".into()), ron::Value::Option(None));
+}
+
+/// A saved game.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveFile {
+    /// The save format version. See [`MIGRATIONS`].
+    pub version: u32,
+    pub metadata: SaveMetadata,
+    /// The level being played.
+    pub level: String,
+    /// The spawn point or checkpoint to continue from, or `None` for the level's start.
+    pub spawn: Option<String>,
+    /// The player's health, or `None` for full health.
+    pub health: Option<u32>,
+    /// How many times the player has died.
+    pub deaths: u32,
+    /// How many lives the player has left, or `None` for a full set.
+    pub lives: Option<u32>,
+    /// The levels the player has reached.
+    pub unlocked: Vec<String>,
+    /// The ids of the items the player has collected.
+    pub collected: Vec<String>,
+}
+
+/// What the save slot menus show about a save.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveMetadata {
+    /// When the game was saved, in seconds since the Unix epoch.
+    pub saved_at: u64,
+    /// How long the game has been played, in seconds.
+    pub playtime_secs: f64,
+}
+
+impl SaveMetadata {
+    pub fn playtime(&self) -> Duration {
+        Duration::from_secs_f64(self.playtime_secs)
+    }
+
+    /// How long ago the game was saved.
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_time().saturating_sub(self.saved_at))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("Saving isn't supported on this platform")]
+    Unsupported,
+    #[error("Could not read or write the save file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse the save file: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("The save file is invalid: {0}")]
+    Invalid(#[from] ron::Error),
+    #[error("The save file has no version")]
+    NoVersion,
+    #[error("The save file is from a newer version of the game (version {0})")]
+    TooNew(u32),
+}
+
+impl SaveFile {
+    fn dir() -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("solz").join("saves"))
+    }
+
+    fn path(slot: usize) -> Option<PathBuf> {
+        Some(Self::dir()?.join(format!("slot{}.ron", slot + 1)))
+    }
+
+    /// Load the game saved in `slot`, or `None` if the slot is empty or games
+    /// aren't saved on this platform.
+    pub fn load(slot: usize) -> Result<Option<Self>, SaveError> {
+        let Some(path) = Self::path(slot) else {
+            return Ok(None);
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        Self::parse(&text).map(Some)
+    }
+
+    /// Parse a saved game, upgrading it from older versions.
+    fn parse(text: &str) -> Result<Self, SaveError> {
+        let ron::Value::Map(mut map) = ron::from_str(text)? else {
+            return Err(SaveError::NoVersion);
+        };
+        let version_key = ron::Value::String("version".into());
+        let version = match map.remove(&version_key) {
+            Some(ron::Value::Number(version)) => version.as_i64().unwrap_or_default() as u32,
+            _ => return Err(SaveError::NoVersion),
+        };
+        if version > SAVE_VERSION {
+            return Err(SaveError::TooNew(version));
+        }
+        for (index, migrate) in MIGRATIONS.iter().enumerate().skip_while(|(index, _)| {
+            // Migration `index` upgrades version `index + 1`.
+            *index as u32 + 1 < version
+        }) {
+            info!("Upgrading save from version {} to {}", index + 1, index + 2);
+            migrate(&mut map);
+        }
+        map.insert(
+            version_key,
+            ron::Value::Number(i64::from(SAVE_VERSION).into()),
+        );
+        Ok(ron::Value::Map(map).into_rust()?)
+    }
+
+    /// Every slot's save, or `None` for slots that are empty or can't be loaded.
+    pub fn load_all() -> Vec<Option<Self>> {
+        (0..SAVE_SLOTS)
+            .map(|slot| {
+                Self::load(slot).unwrap_or_else(|error| {
+                    warn!("Couldn't load save slot {}: {error}", slot + 1);
+                    None
+                })
+            })
+            .collect()
+    }
+
+    /// The slot of the most recent save, if there is one.
+    pub fn latest_slot() -> Option<usize> {
+        Self::load_all()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(slot, save)| Some((slot, save?.metadata.saved_at)))
+            .max_by_key(|&(_, saved_at)| saved_at)
+            .map(|(slot, _)| slot)
+    }
+
+    fn save(&self, slot: usize) -> Result<(), SaveError> {
+        let path = Self::path(slot).ok_or(SaveError::Unsupported)?;
+        let text = ron::ser::to_string_pretty(self, default())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, text)?;
+        Ok(())
+    }
+
+    fn from_world(world: &World) -> Self {
+        let progression = world.resource::<LevelProgression>();
+        let active_save = world.resource::<ActiveSave>();
+        Self {
+            version: SAVE_VERSION,
+            metadata: SaveMetadata {
+                saved_at: unix_time(),
+                playtime_secs: active_save.playtime.as_secs_f64(),
+            },
+            level: progression.current.clone(),
+            spawn: progression.entry.clone(),
+            health: active_save.health,
+            deaths: active_save.deaths,
+            lives: active_save.lives,
+            unlocked: progression.unlocked.clone(),
+            collected: world
+                .resource::<CollectedItems>()
+                .0
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Put the saved game in place, to be played from `slot`.
+    fn apply(self, world: &mut World, slot: usize) {
+        let mut progression = LevelProgression::from_world(world);
+        progression.current = self.level;
+        progression.entry = self.spawn;
+        for level in self.unlocked {
+            if !progression.is_unlocked(&level) {
+                progression.unlocked.push(level);
+            }
+        }
+        world.insert_resource(progression);
+        world.insert_resource(CollectedItems(self.collected.into_iter().collect()));
+        world.insert_resource(ActiveSave {
+            slot: Some(slot),
+            playtime: self.metadata.playtime(),
+            health: self.health,
+            restore_health: self.health,
+            deaths: self.deaths,
+            lives: self.lives,
+        });
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// The slot being played, and what will be saved to it that isn't kept elsewhere.
+#[derive(Resource, Debug, Default)]
+pub struct ActiveSave {
+    /// The slot to save to, or `None` if no game has been started or loaded.
+    pub slot: Option<usize>,
+    playtime: Duration,
+    /// The player's health, kept up to date while playing.
+    health: Option<u32>,
+    /// Health to give the player when they next spawn, after loading a save.
+    restore_health: Option<u32>,
+    /// How many times the player has died in this game.
+    deaths: u32,
+    /// The player's lives, carried from level to level. `None` for a full set.
+    lives: Option<u32>,
+}
+
+/// Start a new game in `slot`. Enter the loading screen afterwards to play it.
+pub fn new_game(world: &mut World, slot: usize) {
+    let progression = LevelProgression::from_world(world);
+    world.insert_resource(progression);
+    world.insert_resource(CollectedItems::default());
+    world.insert_resource(ActiveSave {
+        slot: Some(slot),
+        ..default()
+    });
+}
+
+/// Load the game saved in `slot`, returning whether there was one. Enter the
+/// loading screen afterwards to play it.
+pub fn load_game(world: &mut World, slot: usize) -> bool {
+    match SaveFile::load(slot) {
+        Ok(Some(save)) => {
+            save.apply(world, slot);
+            true
+        }
+        Ok(None) => false,
+        Err(error) => {
+            error!("Couldn't load save slot {}: {error}", slot + 1);
+            false
+        }
+    }
+}
+
+/// Save the game to the active slot, if there is one.
+pub fn save_game(world: &mut World) {
+    let Some(slot) = world.resource::<ActiveSave>().slot else {
+        return;
+    };
+    if SaveFile::path(slot).is_none() {
+        return;
+    }
+    if let Err(error) = SaveFile::from_world(world).save(slot) {
+        error!("Couldn't save to slot {}: {error}", slot + 1);
+    }
+}
+
+fn count_death(
+    trigger: Trigger<Died>,
+    mut active_save: ResMut<ActiveSave>,
+    player_query: Query<(), With<Player>>,
+) {
+    if player_query.contains(trigger.target()) {
+        active_save.deaths += 1;
+    }
+}
+
+fn save_after_respawn(_: Trigger<Respawned>, mut commands: Commands) {
+    commands.queue(save_game);
+}
+
+fn count_playtime(time: Res<Time>, mut active_save: ResMut<ActiveSave>) {
+    active_save.playtime += time.delta();
+}
+
+fn restore_player_health(
+    mut active_save: ResMut<ActiveSave>,
+    mut player_query: Query<&mut Health, Added<Player>>,
+) {
+    if active_save.restore_health.is_none() {
+        return;
+    }
+    for mut health in &mut player_query {
+        if let Some(saved) = active_save.restore_health.take() {
+            health.current = saved.clamp(1, health.max);
+        }
+    }
+}
+
+/// Carry the player's lives over from the previous level, or from the save.
+fn restore_player_lives(
+    active_save: Res<ActiveSave>,
+    mut player_query: Query<&mut Lives, Added<Player>>,
+) {
+    let Some(saved) = active_save.lives else {
+        return;
+    };
+    for mut lives in &mut player_query {
+        lives.0 = saved;
+    }
+}
+
+fn track_player_lives(
+    mut active_save: ResMut<ActiveSave>,
+    player_query: Query<&Lives, (With<Player>, Changed<Lives>)>,
+) {
+    for lives in &player_query {
+        if active_save.lives != Some(lives.0) {
+            active_save.lives = Some(lives.0);
+        }
+    }
+}
+
+/// Running out of lives ends the game, but the player gets a full set to try again.
+fn refill_lives(mut active_save: ResMut<ActiveSave>) {
+    active_save.lives = None;
+}
+
+fn track_player_health(
+    mut active_save: ResMut<ActiveSave>,
+    player_query: Query<&Health, (With<Player>, Changed<Health>)>,
+) {
+    for health in &player_query {
+        // Dead players come back at full health, whether they respawn or retry.
+        let current = (health.current < health.max && !health.is_dead()).then_some(health.current);
+        if active_save.health != current {
+            active_save.health = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION_1: &str = r#"(
+        version: 1,
+        metadata: (saved_at: 1700000000, playtime_secs: 90.5),
+        level: "meadow",
+        spawn: Some("checkpoint"),
+        health: Some(2),
+        unlocked: ["meadow"],
+        collected: [],
+    )"#;
+
+    #[test]
+    fn upgrades_old_saves() {
+        let save = SaveFile::parse(VERSION_1).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.deaths, 0);
+        assert_eq!(save.lives, None);
+        assert_eq!(save.level, "meadow");
+        assert_eq!(save.spawn.as_deref(), Some("checkpoint"));
+        assert_eq!(save.health, Some(2));
+    }
+
+    #[test]
+    fn rejects_saves_from_newer_versions() {
+        let text = VERSION_1.replace("version: 1", &format!("version: {}", SAVE_VERSION + 1));
+        assert!(matches!(
+            SaveFile::parse(&text),
+            Err(SaveError::TooNew(version)) if version == SAVE_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+//! The screen shown when the player runs out of lives.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{progression::LevelProgression, stats::LevelStats},
+    input::{Action, action_just_pressed},
+    screens::{Screen, level_summary},
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::GameOver), spawn_game_over_screen);
+    app.add_systems(
+        Update,
+        (
+            retry_level.run_if(
+                action_just_pressed(Action::Confirm).or(action_just_pressed(Action::Retry)),
+            ),
+            quit_to_title.run_if(action_just_pressed(Action::Back)),
+        )
+            .run_if(in_state(Screen::GameOver)),
+    );
+}
+
+fn spawn_game_over_screen(mut commands: Commands, stats: Res<LevelStats>) {
+    commands.spawn((
+        widget::ui_root("Game Over Screen"),
+        StateScoped(Screen::GameOver),
+        children![
+            widget::header("Game over"),
+            level_summary(&stats),
+            widget::button("Retry", retry_level_on_click),
+            widget::button("Quit to title", quit_to_title_on_click),
+        ],
+    ));
+}
+
+fn retry_level_on_click(
+    _: Trigger<Pointer<Click>>,
+    progression: ResMut<LevelProgression>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    retry_level(progression, next_screen);
+}
+
+/// Start the level over from the beginning, rather than the last checkpoint.
+fn retry_level(
+    mut progression: ResMut<LevelProgression>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    progression.entry = None;
+    next_screen.set(Screen::Loading);
+}
+
+fn quit_to_title_on_click(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn quit_to_title(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
@@ -1,25 +1,33 @@
 //! The screen state for the main gameplay.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{prelude::*, ui::Val::*};
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    audio::DuckMusic,
+    demo::level::spawn_level,
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
 
-    // Toggle pause on key press.
+    // Toggle pause with the pause action. Back also opens the pause menu, but
+    // closes submenus one at a time instead of leaving the menu entirely.
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                    .and(action_just_pressed(Action::Pause).or(action_just_pressed(Action::Back))),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(action_just_pressed(Action::Pause)),
             ),
         ),
     );
@@ -38,6 +46,9 @@ fn pause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(true));
 }
 
+/// How loud the music is while the game is paused, relative to normal.
+const PAUSED_MUSIC_LEVEL: f32 = 0.3;
+
 fn spawn_pause_overlay(mut commands: Commands) {
     commands.spawn((
         Name::new("Pause Overlay"),
@@ -48,6 +59,8 @@ fn spawn_pause_overlay(mut commands: Commands) {
         },
         GlobalZIndex(1),
         BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        // The overlay stays up in submenus opened from the pause menu, so the music stays down too.
+        DuckMusic(PAUSED_MUSIC_LEVEL),
         StateScoped(Pause(true)),
     ));
 }
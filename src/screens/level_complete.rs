@@ -0,0 +1,85 @@
+//! The screen shown after finishing a level, before moving on to the next one.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{
+        progression::{LevelProgression, LevelRegistry},
+        stats::LevelStats,
+    },
+    input::{Action, action_just_pressed},
+    screens::{Screen, level_summary},
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::LevelComplete), spawn_level_complete_screen);
+    app.add_systems(
+        Update,
+        (
+            enter_next_level.run_if(action_just_pressed(Action::Confirm)),
+            retry_level.run_if(action_just_pressed(Action::Retry)),
+            quit_to_title.run_if(action_just_pressed(Action::Back)),
+        )
+            .run_if(in_state(Screen::LevelComplete)),
+    );
+}
+
+fn spawn_level_complete_screen(
+    mut commands: Commands,
+    stats: Res<LevelStats>,
+    registry: Res<LevelRegistry>,
+) {
+    let name = registry
+        .get(&stats.level)
+        .map_or(stats.level.as_str(), |level| level.name);
+    commands.spawn((
+        widget::ui_root("Level Complete Screen"),
+        StateScoped(Screen::LevelComplete),
+        children![
+            widget::header(format!("{name} complete!")),
+            level_summary(&stats),
+            widget::button("Next level", enter_next_level_on_click),
+            widget::button("Retry", retry_level_on_click),
+            widget::button("Quit to title", quit_to_title_on_click),
+        ],
+    ));
+}
+
+/// The exit that completed the level already moved the progression on.
+fn enter_next_level_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Loading);
+}
+
+fn enter_next_level(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Loading);
+}
+
+fn retry_level_on_click(
+    _: Trigger<Pointer<Click>>,
+    stats: Res<LevelStats>,
+    progression: ResMut<LevelProgression>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    retry_level(stats, progression, next_screen);
+}
+
+fn retry_level(
+    stats: Res<LevelStats>,
+    mut progression: ResMut<LevelProgression>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    progression.go_to(&stats.level, None);
+    next_screen.set(Screen::Loading);
+}
+
+fn quit_to_title_on_click(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn quit_to_title(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
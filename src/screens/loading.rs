@@ -1,27 +1,111 @@
 //! A loading screen during which game assets are loaded if necessary.
 //! This reduces stuttering, especially for audio on Wasm.
+//!
+//! If an asset fails to load, the screen says which one and offers to retry.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
 
-use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::{
+    asset_tracking::{ResourceHandles, retry_failed_loads},
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        (
+            update_loading_progress,
+            enter_gameplay_screen.run_if(all_assets_loaded),
+        )
+            .run_if(in_state(Screen::Loading)),
     );
+
+    app.register_type::<LoadingLabel>();
+    app.register_type::<LoadFailureMessage>();
 }
 
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
         StateScoped(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![
+            (widget::label("Loading..."), LoadingLabel),
+            widget::progress_bar(),
+            (
+                Name::new("Load Failure"),
+                Node {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(20.0),
+                    max_width: Percent(80.0),
+                    ..default()
+                },
+                LoadFailureMessage,
+                children![
+                    (
+                        widget::label(""),
+                        TextLayout::new_with_justify(JustifyText::Center)
+                    ),
+                    widget::button("Retry", retry),
+                ],
+            ),
+        ],
     ));
 }
 
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadingLabel;
+
+/// Shown when some assets fail to load.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadFailureMessage;
+
+fn update_loading_progress(
+    resource_handles: Res<ResourceHandles>,
+    mut label: Single<&mut Text, With<LoadingLabel>>,
+    mut bar: Single<&mut ProgressBar>,
+    mut failure_message: Single<(&mut Node, &Children), With<LoadFailureMessage>>,
+    mut text_query: Query<&mut Text, Without<LoadingLabel>>,
+) {
+    label.set_if_neq(Text(format!(
+        "Loading... {}/{}",
+        resource_handles.loaded(),
+        resource_handles.total()
+    )));
+    bar.set_if_neq(ProgressBar(resource_handles.progress()));
+
+    let failures = resource_handles.failures();
+    let (node, children) = &mut *failure_message;
+    let display = if failures.is_empty() {
+        Display::None
+    } else {
+        Display::Flex
+    };
+    if node.display != display {
+        node.display = display;
+    }
+
+    let message = failures
+        .iter()
+        .map(|failure| format!("Couldn't load {}: {}", failure.path, failure.error))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut texts = text_query.iter_many_mut(children.iter());
+    while let Some(mut text) = texts.fetch_next() {
+        text.set_if_neq(Text(message.clone()));
+    }
+}
+
+fn retry(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.queue(retry_failed_loads);
+}
+
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Gameplay);
 }
@@ -1,17 +1,27 @@
 //! The game's main screen states and transitions between them.
 
+mod game_over;
 mod gameplay;
+mod level_complete;
 mod loading;
 mod splash;
 mod title;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    demo::stats::{LevelStats, format_time},
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_computed_state::<InGame>();
 
     app.add_plugins((
+        game_over::plugin,
         gameplay::plugin,
+        level_complete::plugin,
         loading::plugin,
         splash::plugin,
         title::plugin,
@@ -27,4 +37,43 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    GameOver,
+    LevelComplete,
+}
+
+/// The screens that need the game's assets: loading them, playing, and the
+/// screens between levels.
+/// Gameplay-only resources are loaded in this state, so they're ready by the
+/// time the loading screen finishes and dropped when returning to the title.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = Screen;
+
+    fn compute(screen: Screen) -> Option<Self> {
+        matches!(
+            screen,
+            Screen::Loading | Screen::Gameplay | Screen::GameOver | Screen::LevelComplete
+        )
+        .then_some(Self)
+    }
+}
+
+/// How a level went, for the screens shown when it ends.
+fn level_summary(stats: &LevelStats) -> impl Bundle {
+    (
+        Name::new("Level Summary"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(10.0),
+            ..default()
+        },
+        children![
+            widget::label(format!("Time: {}", format_time(stats.time))),
+            widget::label(format!("Deaths: {}", stats.deaths)),
+            widget::label(format!("Items collected: {}", stats.collected)),
+        ],
+    )
 }
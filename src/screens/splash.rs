@@ -2,11 +2,15 @@
 
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
-    input::common_conditions::input_just_pressed,
     prelude::*,
 };
 
-use crate::{AppSystems, screens::Screen, theme::prelude::*};
+use crate::{
+    AppSystems,
+    input::{Action, action_just_pressed},
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Spawn splash screen.
@@ -36,11 +40,10 @@ pub(super) fn plugin(app: &mut App) {
             .run_if(in_state(Screen::Splash)),
     );
 
-    // Exit the splash screen early if the player hits escape.
+    // Exit the splash screen early if the player presses back.
     app.add_systems(
         Update,
-        enter_title_screen
-            .run_if(input_just_pressed(KeyCode::Escape).and(in_state(Screen::Splash))),
+        enter_title_screen.run_if(action_just_pressed(Action::Back).and(in_state(Screen::Splash))),
     );
 }
 
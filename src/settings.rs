@@ -0,0 +1,279 @@
+//! User settings that persist between runs.
+//!
+//! Settings are stored as RON in the platform's config directory (e.g.
+//! `~/.config/solz/settings.ron` on Linux). They are read while the app is
+//! being built, so they're in place before the first frame. On platforms
+//! without a config directory (such as the web) settings simply aren't saved.
+//!
+//! The game reads and changes the usual resources ([`GlobalVolume`],
+//! [`ChannelVolumes`], [`InputMap`], [`Accessibility`], [`DisplayMode`],
+//! [`UiScale`] and [`HudSettings`]). Whenever one of them
+//! changes, the settings file is rewritten after a short delay, so dragging a
+//! value around doesn't hammer the disk.
+
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::Accessibility,
+    audio::ChannelVolumes,
+    demo::hud::HudSettings,
+    input::{Action, Binding, InputMap},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    SettingsFile::load().insert_resources(app);
+
+    app.register_type::<DisplayMode>();
+    app.add_systems(
+        Update,
+        apply_display_mode.run_if(resource_changed::<DisplayMode>),
+    );
+
+    app.init_resource::<PendingSave>();
+    app.add_systems(
+        Update,
+        (schedule_save.run_if(settings_changed), save_when_settled).chain(),
+    );
+    // Don't lose changes made just before quitting.
+    app.add_systems(Last, save_now.run_if(on_event::<AppExit>));
+}
+
+/// How long settings must stay unchanged before they're written to disk.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// How the primary window is shown.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Windowed => "Windowed",
+            Self::BorderlessFullscreen => "Borderless",
+            Self::Fullscreen => "Fullscreen",
+        }
+    }
+
+    /// The next mode, for cycling through them in a menu.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Windowed => Self::BorderlessFullscreen,
+            Self::BorderlessFullscreen => Self::Fullscreen,
+            Self::Fullscreen => Self::Windowed,
+        }
+    }
+
+    /// The previous mode, for cycling through them in a menu.
+    pub fn previous(self) -> Self {
+        self.next().next()
+    }
+
+    fn window_mode(self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::BorderlessFullscreen => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            Self::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+}
+
+fn apply_display_mode(
+    display_mode: Res<DisplayMode>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    window.mode = display_mode.window_mode();
+}
+
+/// The quietest volume the settings allow.
+pub const MIN_VOLUME: f32 = 0.0;
+/// The loudest volume the settings allow.
+pub const MAX_VOLUME: f32 = 3.0;
+
+/// The smallest [`UiScale`] the settings allow.
+pub const MIN_UI_SCALE: f32 = 0.5;
+/// The largest [`UiScale`] the settings allow.
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+/// The on-disk settings format. Every field has a default, so settings files
+/// from older versions of the game still load.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+struct SettingsFile {
+    master_volume: f32,
+    channel_volumes: ChannelVolumes,
+    display_mode: DisplayMode,
+    ui_scale: f32,
+    accessibility: Accessibility,
+    hud: HudSettings,
+    /// Bindings for each action. Actions missing from the file use their default bindings.
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            channel_volumes: default(),
+            display_mode: default(),
+            ui_scale: 1.0,
+            accessibility: default(),
+            hud: default(),
+            bindings: default(),
+        }
+    }
+}
+
+impl SettingsFile {
+    fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("solz").join("settings.ron"))
+    }
+
+    fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                info!("No settings at {}, using defaults", path.display());
+                return Self::default();
+            }
+            Err(error) => {
+                warn!(
+                    "Couldn't read settings from {}, using defaults: {error}",
+                    path.display()
+                );
+                return Self::default();
+            }
+        };
+        ron::from_str(&text).unwrap_or_else(|error| {
+            warn!(
+                "Couldn't parse settings in {}, using defaults: {error}",
+                path.display()
+            );
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let text = match ron::ser::to_string_pretty(self, default()) {
+            Ok(text) => text,
+            Err(error) => {
+                error!("Couldn't serialize settings: {error}");
+                return;
+            }
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, text));
+        if let Err(error) = result {
+            error!("Couldn't save settings to {}: {error}", path.display());
+        }
+    }
+
+    fn insert_resources(&self, app: &mut App) {
+        let mut input_map = InputMap::default();
+        for (&action, bindings) in &self.bindings {
+            input_map.bindings.insert(action, bindings.clone());
+        }
+        app.insert_resource(input_map);
+        // A hand-edited file could ask for a negative or deafening volume.
+        let volume = |linear: f32| linear.clamp(MIN_VOLUME, MAX_VOLUME);
+        app.insert_resource(GlobalVolume::new(Volume::Linear(volume(
+            self.master_volume,
+        ))));
+        app.insert_resource(ChannelVolumes {
+            music: volume(self.channel_volumes.music),
+            sound_effects: volume(self.channel_volumes.sound_effects),
+            ui: volume(self.channel_volumes.ui),
+        });
+        app.insert_resource(self.display_mode);
+        // A hand-edited file could make the UI unusably small or large.
+        app.insert_resource(UiScale(self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)));
+        app.insert_resource(self.accessibility);
+        app.insert_resource(self.hud);
+    }
+
+    fn from_world(world: &World) -> Self {
+        let input_map = world.resource::<InputMap>();
+        Self {
+            master_volume: world.resource::<GlobalVolume>().volume.to_linear(),
+            channel_volumes: *world.resource::<ChannelVolumes>(),
+            display_mode: *world.resource::<DisplayMode>(),
+            ui_scale: world.resource::<UiScale>().0,
+            accessibility: *world.resource::<Accessibility>(),
+            hud: *world.resource::<HudSettings>(),
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, input_map.bindings(action).to_vec()))
+                .collect(),
+        }
+    }
+}
+
+/// Time left until the settings are saved, if they have unsaved changes.
+#[derive(Resource, Debug, Default)]
+struct PendingSave(Option<Timer>);
+
+fn settings_changed(
+    global_volume: Res<GlobalVolume>,
+    channel_volumes: Res<ChannelVolumes>,
+    display_mode: Res<DisplayMode>,
+    ui_scale: Res<UiScale>,
+    accessibility: Res<Accessibility>,
+    hud_settings: Res<HudSettings>,
+    input_map: Res<InputMap>,
+) -> bool {
+    // Resources count as changed when they're first inserted, but that's just the loaded settings.
+    let changed = |is_changed: bool, is_added: bool| is_changed && !is_added;
+    changed(global_volume.is_changed(), global_volume.is_added())
+        || changed(channel_volumes.is_changed(), channel_volumes.is_added())
+        || changed(display_mode.is_changed(), display_mode.is_added())
+        || changed(ui_scale.is_changed(), ui_scale.is_added())
+        || changed(accessibility.is_changed(), accessibility.is_added())
+        || changed(hud_settings.is_changed(), hud_settings.is_added())
+        || changed(input_map.is_changed(), input_map.is_added())
+}
+
+fn schedule_save(mut pending_save: ResMut<PendingSave>) {
+    pending_save.0 = Some(Timer::new(SAVE_DELAY, TimerMode::Once));
+}
+
+fn save_when_settled(world: &mut World) {
+    let delta = world.resource::<Time<Real>>().delta();
+    let mut pending_save = world.resource_mut::<PendingSave>();
+    let Some(timer) = &mut pending_save.0 else {
+        return;
+    };
+    if timer.tick(delta).finished() {
+        pending_save.0 = None;
+        SettingsFile::from_world(world).save();
+    }
+}
+
+fn save_now(world: &mut World) {
+    if world.resource_mut::<PendingSave>().0.take().is_some() {
+        SettingsFile::from_world(world).save();
+    }
+}
@@ -1,15 +1,18 @@
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::sound_effect};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::{SoundBank, ui_sound_from_bank},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<InteractionPalette>();
     app.add_systems(Update, apply_interaction_palette);
 
     app.register_type::<InteractionAssets>();
-    app.load_resource::<InteractionAssets>();
-    app.add_observer(play_on_hover_sound_effect);
-    app.add_observer(play_on_click_sound_effect);
+    app.load_resource::<InteractionAssets>("manifests/interaction.assets.ron");
+    app.add_observer(play_on_hover_sound);
+    app.add_observer(play_on_click_sound);
 }
 
 /// Palette for widget interactions. Add this to an entity that supports
@@ -39,26 +42,16 @@ fn apply_interaction_palette(
     }
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, Default)]
 #[reflect(Resource)]
 struct InteractionAssets {
     #[dependency]
-    hover: Handle<AudioSource>,
+    hover: Handle<SoundBank>,
     #[dependency]
-    click: Handle<AudioSource>,
+    click: Handle<SoundBank>,
 }
 
-impl FromWorld for InteractionAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            hover: assets.load("audio/sound_effects/button_hover.ogg"),
-            click: assets.load("audio/sound_effects/button_click.ogg"),
-        }
-    }
-}
-
-fn play_on_hover_sound_effect(
+fn play_on_hover_sound(
     trigger: Trigger<Pointer<Over>>,
     mut commands: Commands,
     interaction_assets: Option<Res<InteractionAssets>>,
@@ -69,11 +62,11 @@ fn play_on_hover_sound_effect(
     };
 
     if interaction_query.contains(trigger.target()) {
-        commands.spawn(sound_effect(interaction_assets.hover.clone()));
+        commands.spawn(ui_sound_from_bank(interaction_assets.hover.clone()));
     }
 }
 
-fn play_on_click_sound_effect(
+fn play_on_click_sound(
     trigger: Trigger<Pointer<Click>>,
     mut commands: Commands,
     interaction_assets: Option<Res<InteractionAssets>>,
@@ -84,6 +77,6 @@ fn play_on_click_sound_effect(
     };
 
     if interaction_query.contains(trigger.target()) {
-        commands.spawn(sound_effect(interaction_assets.click.clone()));
+        commands.spawn(ui_sound_from_bank(interaction_assets.click.clone()));
     }
 }
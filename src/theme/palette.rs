@@ -14,3 +14,8 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+
+/// #d9263a
+pub const HEALTH_FULL: Color = Color::srgb(0.851, 0.149, 0.227);
+/// #33333399
+pub const HEALTH_EMPTY: Color = Color::srgba(0.2, 0.2, 0.2, 0.6);
@@ -0,0 +1,31 @@
+//! Bars that fill up to show progress.
+
+use bevy::{prelude::*, ui::Val::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ProgressBar>();
+    app.register_type::<ProgressBarFill>();
+    app.add_systems(Update, update_progress_bars);
+}
+
+/// How full a [`widget::progress_bar`](crate::theme::widget::progress_bar) is, from 0 to 1.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct ProgressBar(pub f32);
+
+/// The part of a progress bar that fills up.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct ProgressBarFill;
+
+fn update_progress_bars(
+    bar_query: Query<(&ProgressBar, &Children), Changed<ProgressBar>>,
+    mut fill_query: Query<&mut Node, With<ProgressBarFill>>,
+) {
+    for (bar, children) in &bar_query {
+        let mut fills = fill_query.iter_many_mut(children);
+        while let Some(mut fill) = fills.fetch_next() {
+            fill.width = Percent(bar.0.clamp(0.0, 1.0) * 100.0);
+        }
+    }
+}
@@ -8,7 +8,11 @@ use bevy::{
     ui::Val::*,
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{
+    interaction::InteractionPalette,
+    palette::*,
+    progress_bar::{ProgressBar, ProgressBarFill},
+};
 
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
@@ -49,6 +53,31 @@ pub fn label(text: impl Into<String>) -> impl Bundle {
     )
 }
 
+/// An empty progress bar. Set its [`ProgressBar`] to fill it.
+pub fn progress_bar() -> impl Bundle {
+    (
+        Name::new("Progress Bar"),
+        Node {
+            width: Px(380.0),
+            height: Px(24.0),
+            border: UiRect::all(Px(3.0)),
+            ..default()
+        },
+        BorderColor(BUTTON_BACKGROUND),
+        ProgressBar(0.0),
+        children![(
+            Name::new("Progress Bar Fill"),
+            Node {
+                width: Percent(0.0),
+                height: Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(BUTTON_HOVERED_BACKGROUND),
+            ProgressBarFill,
+        )],
+    )
+}
+
 /// A large rounded button with text and an action defined as an [`Observer`].
 pub fn button<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -58,6 +87,7 @@ where
 {
     button_base(
         text,
+        40.0,
         action,
         (
             Node {
@@ -81,6 +111,7 @@ where
 {
     button_base(
         text,
+        40.0,
         action,
         Node {
             width: Px(30.0),
@@ -92,9 +123,31 @@ where
     )
 }
 
+/// A medium-sized button with smaller text, for rows of options such as key bindings.
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: Event,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        24.0,
+        action,
+        Node {
+            width: Px(170.0),
+            height: Px(36.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    )
+}
+
 /// A simple button with text and an action defined as an [`Observer`]. The button's layout is provided by `button_bundle`.
 fn button_base<E, B, M, I>(
     text: impl Into<String>,
+    font_size: f32,
     action: I,
     button_bundle: impl Bundle,
 ) -> impl Bundle
@@ -108,7 +161,7 @@ where
     (
         Name::new("Button"),
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent
                 .spawn((
                     Name::new("Button Inner"),
@@ -122,7 +175,7 @@ where
                     children![(
                         Name::new("Button Text"),
                         Text(text),
-                        TextFont::from_font_size(40.0),
+                        TextFont::from_font_size(font_size),
                         TextColor(BUTTON_TEXT),
                         // Don't bubble picking events from the text up to the button.
                         Pickable::IGNORE,